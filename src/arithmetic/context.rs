@@ -0,0 +1,609 @@
+//! precomputed modular contexts with pluggable reduction strategies
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+use super::limbs::{adc, bits_limbs, biguint_from_limbs, cmp_limbs, mac_row, montgomery_mul_limbs, mul_limbs, sbb, sub_limbs};
+use super::{add_mod, mod_inv, sub_mod, Number};
+use crate::error::Result;
+
+/// reduction strategy for a fixed modulus. numbers are converted into the strategy's
+/// form once, then all field operations run on that form without dividing by the modulus
+pub trait Reducer<T: Number> {
+    /// returns the modulus of the context
+    fn modulus(&self) -> &T;
+
+    /// converts a number into the form of the context
+    fn encode(&self, number: &T) -> T;
+
+    /// converts a number from the form of the context back to its residue
+    fn decode(&self, number: &T) -> T;
+
+    /// multiplies two encoded numbers
+    fn mul(&self, number1: &T, number2: &T) -> T;
+
+    /// returns the encoded one
+    fn one(&self) -> T {
+        self.encode(&T::one())
+    }
+
+    /// adds two encoded numbers
+    fn add(&self, number1: &T, number2: &T) -> T {
+        add_mod(number1, number2, self.modulus())
+    }
+
+    /// subtracts two encoded numbers
+    fn sub(&self, number1: &T, number2: &T) -> T {
+        sub_mod(number1, number2, self.modulus())
+    }
+
+    /// negates an encoded number
+    fn neg(&self, number: &T) -> T {
+        sub_mod(&T::zero(), number, self.modulus())
+    }
+
+    /// squares an encoded number
+    fn square(&self, number: &T) -> T {
+        self.mul(number, number)
+    }
+
+    /// runs square_and_multiply on an encoded base with a plain exponent
+    fn pow(&self, base: &T, exponent: &T) -> T {
+        let mut result = self.one();
+
+        for index in (0..=exponent.msb_position()).rev() {
+            result = self.square(&result);
+            if exponent.bit(index) {
+                result = self.mul(&result, base);
+            }
+        }
+
+        return result;
+    }
+
+    /// returns the encoded inverse of an encoded number, or the divisor it shares with the modulus
+    fn inv(&self, number: &T) -> Result<T, T> {
+        mod_inv(&self.decode(number), self.modulus()).map(|inverse| self.encode(&inverse))
+    }
+}
+
+/// general reducer, multiplies with mod_mul and keeps numbers as plain residues
+#[derive(Clone, Debug)]
+pub struct Plain<T: Number> {
+    modulus: T,
+}
+
+impl<T: Number> Plain<T> {
+    pub fn new(modulus: &T) -> Self {
+        Plain { modulus: modulus.clone() }
+    }
+}
+
+impl<T: Number> Reducer<T> for Plain<T> {
+    fn modulus(&self) -> &T {
+        &self.modulus
+    }
+
+    fn encode(&self, number: &T) -> T {
+        number.mod_floor(&self.modulus)
+    }
+
+    fn decode(&self, number: &T) -> T {
+        number.clone()
+    }
+
+    fn mul(&self, number1: &T, number2: &T) -> T {
+        number1.mul_mod(number2, &self.modulus)
+    }
+}
+
+/// montgomery reducer for odd moduli with R = 2^(64 * limbs), numbers are kept as a * R mod n
+#[derive(Clone, Debug)]
+pub struct Montgomery<T: Number> {
+    modulus: T,
+    limbs: Vec<u64>,
+    n_prime: u64,
+    r: T,
+    r_squared: Vec<u64>,
+}
+
+impl<T: Number> Montgomery<T> {
+    /// precomputes R mod n, R² mod n and N' = -n⁻¹ mod 2^64, returns None for even moduli
+    pub fn new(modulus: &T) -> Option<Self> {
+        if modulus.is_even() || *modulus <= T::one() {
+            return None;
+        }
+        let limbs = modulus.to_limbs();
+
+        // newton iteration doubles the correct low bits of n⁻¹ mod 2^64 every step
+        let mut inverse: u64 = 1;
+        for _ in 0..6 {
+            inverse = inverse.wrapping_mul(2u64.wrapping_sub(limbs[0].wrapping_mul(inverse)));
+        }
+
+        // double up to R and R² instead of dividing numbers wider than the type
+        let mut r = T::one();
+        for _ in 0..64 * limbs.len() {
+            r = add_mod(&r, &r, modulus);
+        }
+        let mut r_squared = r.clone();
+        for _ in 0..64 * limbs.len() {
+            r_squared = add_mod(&r_squared, &r_squared, modulus);
+        }
+
+        Some(Montgomery {
+            modulus: modulus.clone(),
+            n_prime: inverse.wrapping_neg(),
+            r_squared: Self::pad(r_squared.to_limbs(), limbs.len()),
+            limbs,
+            r,
+        })
+    }
+
+    /// returns the value of R mod n
+    pub fn r(&self) -> &T {
+        &self.r
+    }
+
+    /// returns the precomputed N' = -n⁻¹ mod 2^64
+    pub fn n_prime(&self) -> u64 {
+        self.n_prime
+    }
+
+    fn pad(mut limbs: Vec<u64>, size: usize) -> Vec<u64> {
+        limbs.resize(size, 0);
+        limbs
+    }
+
+    fn reduce(&self, number1: &[u64], number2: &[u64]) -> T {
+        T::from_limbs(&montgomery_mul_limbs(number1, number2, &self.limbs, self.n_prime))
+    }
+}
+
+impl<T: Number> Reducer<T> for Montgomery<T> {
+    fn modulus(&self) -> &T {
+        &self.modulus
+    }
+
+    fn encode(&self, number: &T) -> T {
+        let number = Self::pad(number.mod_floor(&self.modulus).to_limbs(), self.limbs.len());
+        self.reduce(&number, &self.r_squared)
+    }
+
+    fn decode(&self, number: &T) -> T {
+        let one = Self::pad(vec![1], self.limbs.len());
+        self.reduce(&Self::pad(number.to_limbs(), self.limbs.len()), &one)
+    }
+
+    fn mul(&self, number1: &T, number2: &T) -> T {
+        let size = self.limbs.len();
+        self.reduce(&Self::pad(number1.to_limbs(), size), &Self::pad(number2.to_limbs(), size))
+    }
+
+    fn one(&self) -> T {
+        self.r.clone()
+    }
+}
+
+/// barrett reducer for general moduli, replaces the division by a multiplication with
+/// the precomputed μ = ⌊b^2k / n⌋ for b = 2^64 and k limbs
+#[derive(Clone, Debug)]
+pub struct Barrett<T: Number> {
+    modulus: T,
+    limbs: Vec<u64>,
+    mu: Vec<u64>,
+}
+
+impl<T: Number> Barrett<T> {
+    /// precomputes μ, returns None for moduli below two
+    pub fn new(modulus: &T) -> Option<Self> {
+        if *modulus <= T::one() {
+            return None;
+        }
+        let limbs = modulus.to_limbs();
+
+        // one division by n when creating the context, none afterwards
+        let mu = (BigUint::from(1u8) << (128 * limbs.len())) / biguint_from_limbs(&limbs);
+
+        Some(Barrett { modulus: modulus.clone(), mu: mu.to_u64_digits(), limbs })
+    }
+
+    /// reduces a number below n² modulo n
+    fn reduce(&self, number: &[u64]) -> T {
+        let k = self.limbs.len();
+
+        // estimate the quotient, which is at most two below the exact one
+        let q1 = &number[(k - 1).min(number.len())..];
+        let q3 = mul_limbs(q1, &self.mu).split_off((k + 1).min(q1.len() + self.mu.len()));
+
+        // the remainder estimate is exact modulo b^(k+1)
+        let r1: Vec<u64> = (0..=k).map(|i| *number.get(i).unwrap_or(&0)).collect();
+        let r2: Vec<u64> = mul_limbs(&q3, &self.limbs).into_iter().chain(std::iter::repeat(0)).take(k + 1).collect();
+        let mut result = sub_limbs(&r1, &r2);
+
+        while cmp_limbs(&result, &self.limbs).is_ge() {
+            result = sub_limbs(&result, &self.limbs);
+        }
+
+        return T::from_limbs(&result);
+    }
+}
+
+impl<T: Number> Reducer<T> for Barrett<T> {
+    fn modulus(&self) -> &T {
+        &self.modulus
+    }
+
+    fn encode(&self, number: &T) -> T {
+        number.mod_floor(&self.modulus)
+    }
+
+    fn decode(&self, number: &T) -> T {
+        number.clone()
+    }
+
+    fn mul(&self, number1: &T, number2: &T) -> T {
+        self.reduce(&mul_limbs(&number1.to_limbs(), &number2.to_limbs()))
+    }
+}
+
+/// reducer for pseudo-mersenne moduli n = 2^k - c with a small c of few signed binary digits,
+/// like 2^255 - 19, 2^127 - 1, 2^62 + 1 or the solinas primes of P-256. as 2^k ≡ c, everything
+/// above bit k is folded onto the bits below with one multiplication by c
+#[derive(Clone, Debug)]
+pub struct PseudoMersenne<T: Number> {
+    modulus: T,
+    limbs: Vec<u64>,
+    k: u64,
+    c: Vec<u64>,
+    negative: bool,
+}
+
+impl<T: Number> PseudoMersenne<T> {
+    /// the most non-zero signed binary digits of c accepted
+    const MAX_TERMS: usize = 5;
+
+    /// bits every fold takes off at least, c has to be below 2^(k - 32)
+    const MIN_GAIN: u64 = 32;
+
+    /// returns None unless the modulus is 2^k - c or 2^k + c with |c| < 2^(k - 32) and c has at
+    /// most five non-zero digits in its non-adjacent form
+    pub fn new(modulus: &T) -> Option<Self> {
+        if *modulus <= T::one() {
+            return None;
+        }
+        let limbs = modulus.to_limbs();
+        let bits = bits_limbs(&limbs);
+
+        // n lies between 2^(bits - 1) and 2^bits, c is positive for the upper and negative for
+        // the lower power
+        for k in [bits, bits - 1] {
+            let c = (BigInt::from(1) << k) - BigInt::from(biguint_from_limbs(&limbs));
+            if c.is_zero() || c.bits() + Self::MIN_GAIN > k || Self::signed_weight(c.magnitude()) > Self::MAX_TERMS {
+                continue;
+            }
+
+            let (sign, c) = c.to_u64_digits();
+            let negative = sign == Sign::Minus;
+            return Some(PseudoMersenne { modulus: modulus.clone(), limbs, k, c, negative });
+        }
+
+        return None;
+    }
+
+    /// returns k of n = 2^k - c
+    pub fn k(&self) -> u64 {
+        self.k
+    }
+
+    /// whether c fits into a single limb, then a fold is a single row of multiplications. a c of
+    /// several limbs like the one of P-256 takes as few as 32 bits off per fold and loses to
+    /// montgomery multiplication
+    pub fn single_limb(&self) -> bool {
+        self.c.len() == 1
+    }
+
+    /// returns the number of non-zero digits in the non-adjacent form of c, which has the
+    /// fewest of all signed binary representations
+    fn signed_weight(c: &BigUint) -> usize {
+        let mut rest = c.clone();
+        let mut weight = 0;
+
+        while !rest.is_zero() {
+            if rest.is_odd() {
+                // the digit ±1 that leaves a multiple of four, so the next digit is zero
+                match (&rest % 4u32).is_one() {
+                    true => rest -= 1u32,
+                    false => rest += 1u32,
+                }
+                weight += 1;
+            }
+            rest >>= 1;
+        }
+
+        return weight;
+    }
+
+    /// reduces a number below n² modulo n. with V = high·2^k + low the number is replaced by
+    /// low + high·c, kept as a sign and a magnitude, which takes at least 32 bits off every
+    /// time, until the magnitude lies in 0..2^(k + 1)
+    fn reduce(&self, mut magnitude: Vec<u64>) -> T {
+        let (low_limbs, low_bits) = ((self.k / 64) as usize, self.k % 64);
+        let mut scratch = vec![0u64; 2 * magnitude.len() + self.c.len()];
+        let (high, product) = scratch.split_at_mut(magnitude.len());
+        let mut negative = false;
+
+        // a magnitude below 2^(k + 1) is below 3n, folding further could cycle between 2^k and
+        // -1 for n = 2^k + 1, so the last steps subtract n
+        loop {
+            let length = magnitude.iter().rposition(|limb| *limb != 0).map_or(0, |index| index + 1);
+            if bits_limbs(&magnitude[..length]) <= self.k + 1 {
+                break;
+            }
+
+            let high = &mut high[..length - low_limbs];
+            for (index, word) in (low_limbs..).zip(high.iter_mut()) {
+                *word = match low_bits {
+                    0 => magnitude[index],
+                    _ => magnitude[index] >> low_bits | magnitude.get(index + 1).unwrap_or(&0) << (64 - low_bits),
+                };
+            }
+            let product = &mut product[..high.len() + self.c.len()];
+            product.fill(0);
+            for (i, &factor) in self.c.iter().enumerate() {
+                product[i + high.len()] = mac_row(&mut product[i..i + high.len()], high, factor);
+            }
+
+            // keep low in place, the product is below 2^(length·64 - 32) and fits next to it
+            magnitude[low_limbs + 1..].fill(0);
+            magnitude[low_limbs] &= (1u64 << low_bits).wrapping_sub(1);
+            let mut carry = 0;
+            match self.negative {
+                false => for (index, limb) in magnitude.iter_mut().enumerate() {
+                    (*limb, carry) = adc(*limb, *product.get(index).unwrap_or(&0), carry);
+                },
+                true if cmp_limbs(&magnitude, product).is_ge() => for (index, limb) in magnitude.iter_mut().enumerate() {
+                    (*limb, carry) = sbb(*limb, *product.get(index).unwrap_or(&0), carry);
+                },
+                true => {
+                    negative = !negative;
+                    for (index, limb) in magnitude.iter_mut().enumerate() {
+                        (*limb, carry) = sbb(*product.get(index).unwrap_or(&0), *limb, carry);
+                    }
+                }
+            }
+        }
+
+        while cmp_limbs(&magnitude, &self.limbs).is_ge() {
+            let mut borrow = 0;
+            for (index, limb) in magnitude.iter_mut().enumerate() {
+                (*limb, borrow) = sbb(*limb, *self.limbs.get(index).unwrap_or(&0), borrow);
+            }
+        }
+        magnitude.resize(self.limbs.len(), 0);
+        if negative && magnitude.iter().any(|limb| *limb != 0) {
+            let mut borrow = 0;
+            for (limb, modulus) in magnitude.iter_mut().zip(&self.limbs) {
+                (*limb, borrow) = sbb(*modulus, *limb, borrow);
+            }
+        }
+
+        return T::from_limbs(&magnitude);
+    }
+}
+
+impl<T: Number> Reducer<T> for PseudoMersenne<T> {
+    fn modulus(&self) -> &T {
+        &self.modulus
+    }
+
+    fn encode(&self, number: &T) -> T {
+        number.mod_floor(&self.modulus)
+    }
+
+    fn decode(&self, number: &T) -> T {
+        number.clone()
+    }
+
+    fn mul(&self, number1: &T, number2: &T) -> T {
+        self.reduce(mul_limbs(&number1.to_limbs(), &number2.to_limbs()))
+    }
+}
+
+/// precomputed context of a modulus, dispatches to the chosen reducer
+#[derive(Clone, Debug)]
+pub enum ModContext<T: Number> {
+    Plain(Plain<T>),
+    Montgomery(Montgomery<T>),
+    Barrett(Barrett<T>),
+    PseudoMersenne(PseudoMersenne<T>),
+}
+
+impl<T: Number> ModContext<T> {
+    /// chooses the best reducer for the modulus: folding for pseudo-mersenne moduli with a c
+    /// of one limb, montgomery for other odd moduli, barrett for even ones and the general
+    /// reducer for moduli below two
+    pub fn new(modulus: &T) -> Self {
+        if let Some(reducer) = PseudoMersenne::new(modulus).filter(PseudoMersenne::single_limb) {
+            return ModContext::PseudoMersenne(reducer);
+        }
+        if let Some(reducer) = Montgomery::new(modulus) {
+            return ModContext::Montgomery(reducer);
+        }
+
+        match Barrett::new(modulus) {
+            Some(reducer) => ModContext::Barrett(reducer),
+            None => ModContext::Plain(Plain::new(modulus)),
+        }
+    }
+
+    fn reducer(&self) -> &dyn Reducer<T> {
+        match self {
+            ModContext::Plain(reducer) => reducer,
+            ModContext::Montgomery(reducer) => reducer,
+            ModContext::Barrett(reducer) => reducer,
+            ModContext::PseudoMersenne(reducer) => reducer,
+        }
+    }
+}
+
+impl<T: Number> Reducer<T> for ModContext<T> {
+    fn modulus(&self) -> &T {
+        self.reducer().modulus()
+    }
+
+    fn encode(&self, number: &T) -> T {
+        self.reducer().encode(number)
+    }
+
+    fn decode(&self, number: &T) -> T {
+        self.reducer().decode(number)
+    }
+
+    fn mul(&self, number1: &T, number2: &T) -> T {
+        self.reducer().mul(number1, number2)
+    }
+
+    fn one(&self) -> T {
+        self.reducer().one()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::RandBigInt;
+    use num_traits::Signed;
+    use rand::Rng;
+
+    /// converts a non-negative number of any type to a big integer
+    fn big<T: Number>(number: &T) -> BigInt {
+        BigInt::from_limbs(&number.to_limbs())
+    }
+
+    /// checks the round trip and every multiplication of the reducer against big integers
+    fn check_reducer<T: Number>(reducer: &impl Reducer<T>, values: &[T]) {
+        let modulus = big(reducer.modulus());
+        for a in values {
+            let encoded = reducer.encode(a);
+            assert!(big(&encoded) < modulus);
+            assert_eq!(reducer.decode(&encoded), a.mod_floor(reducer.modulus()), "{} mod {}", a, modulus);
+            assert_eq!(big(&reducer.decode(&reducer.square(&encoded))), big(a).pow(2) % &modulus);
+
+            for b in values {
+                let product = reducer.decode(&reducer.mul(&encoded, &reducer.encode(b)));
+                assert_eq!(big(&product), big(a) * big(b) % &modulus, "{}·{} mod {}", a, b, modulus);
+            }
+        }
+        assert_eq!(reducer.decode(&reducer.one()), T::one().mod_floor(reducer.modulus()));
+    }
+
+    /// returns the edge values of a modulus and a few random ones below it
+    fn values<T: Number>(modulus: &T) -> Vec<T> {
+        let mut rng = rand::thread_rng();
+        let mut values = vec![T::zero(), T::one(), modulus.clone() - T::one(), modulus.clone()];
+        values.extend((0..12).map(|_| T::random_below(&mut rng, modulus)));
+        return values;
+    }
+
+    #[test]
+    fn montgomery_matches_big_integers() {
+        let mut rng = rand::thread_rng();
+
+        for modulus in [3i64, 5, 1_000_003, i64::MAX, i64::MAX - 2, rng.gen_range(1..1 << 62) * 2 + 1] {
+            check_reducer(&Montgomery::new(&modulus).unwrap(), &values(&modulus));
+        }
+        for modulus in [3i128, i128::MAX, i128::MAX - 2, (1 << 64) + 1, rng.gen_range(1..1 << 125) * 2 + 1] {
+            check_reducer(&Montgomery::new(&modulus).unwrap(), &values(&modulus));
+        }
+        let moduli = [
+            BigInt::from(2).pow(255) - 19,
+            BigInt::from(2).pow(192) + 1,
+            BigInt::from(3).pow(200),
+            rng.gen_bigint(300).abs() * 2 + 1,
+        ];
+        for modulus in moduli {
+            let reducer = Montgomery::new(&modulus).unwrap();
+            assert_eq!(big(reducer.r()), (BigInt::from(1) << (64 * modulus.to_limbs().len())) % &modulus);
+            check_reducer(&reducer, &values(&modulus));
+        }
+    }
+
+    #[test]
+    fn pseudo_mersenne_matches_big_integers() {
+        let two = |k: u32| BigInt::from(2).pow(k);
+        let moduli = [
+            two(62) + 1,
+            two(80) + 1,
+            two(127) - 1,
+            two(130) - 5,
+            two(255) - 19,
+            two(256) - two(32) - 977,
+            two(256) - two(224) + two(192) + two(96) - 1,
+            two(384) - two(128) - two(96) + two(32) - 1,
+            two(521) - 1,
+        ];
+        for modulus in moduli {
+            let reducer = PseudoMersenne::new(&modulus).unwrap();
+            check_reducer(&reducer, &values(&modulus));
+
+            // (n - 1)² = 1 needs every digit of c folded, the top one included
+            let minus_one = reducer.encode(&(&modulus - 1));
+            assert_eq!(reducer.decode(&reducer.square(&minus_one)), BigInt::from(1), "{}", modulus);
+            let folding = matches!(ModContext::new(&modulus), ModContext::PseudoMersenne(_));
+            assert_eq!(folding, reducer.single_limb(), "{}", modulus);
+        }
+
+        assert!(matches!(ModContext::new(&(two(127) - 1)), ModContext::PseudoMersenne(_)));
+        assert!(matches!(ModContext::new(&(two(256) - two(224) + two(192) + two(96) - 1)), ModContext::Montgomery(_)));
+
+        for modulus in [(1i64 << 62) + 1, i64::MAX, i64::MAX - 1] {
+            check_reducer(&PseudoMersenne::new(&modulus).unwrap(), &values(&modulus));
+        }
+        for modulus in [i128::MAX, (1 << 100) + 3, (1 << 126) - (1 << 80) + 1] {
+            check_reducer(&PseudoMersenne::new(&modulus).unwrap(), &values(&modulus));
+        }
+    }
+
+    #[test]
+    fn general_moduli_are_no_pseudo_mersenne() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..10_000 {
+            let modulus = rng.gen_range(2..i64::MAX);
+            assert!(PseudoMersenne::new(&modulus).is_none(), "{}", modulus);
+        }
+        for _ in 0..100 {
+            let modulus = rng.gen_bigint(256).abs() + 2;
+            assert!(PseudoMersenne::new(&modulus).is_none(), "{}", modulus);
+        }
+        for modulus in [2i64, 3, 4, 65_537, 1_000_003, 1 << 40] {
+            assert!(PseudoMersenne::new(&modulus).is_none(), "{}", modulus);
+        }
+        assert!(matches!(ModContext::new(&1_000_003i64), ModContext::Montgomery(_)));
+
+        // c has to be small and sparse: six digits or 2^40 + 1 above 2^(64 - 32) are neither
+        let two = |k: u32| BigInt::from(2).pow(k);
+        assert!(PseudoMersenne::new(&(two(256) - 0b1010_1010_1011)).is_none());
+        assert!(PseudoMersenne::new(&(two(64) - two(40) - 1)).is_none());
+        assert!(PseudoMersenne::new(&(two(64) - two(31) - 1)).is_some());
+    }
+
+    #[test]
+    fn even_and_tiny_moduli_fall_back() {
+        for modulus in [-5i64, 0, 1, 2, 4, 1 << 40, i64::MAX - 1] {
+            assert!(Montgomery::new(&modulus).is_none(), "{}", modulus);
+        }
+        assert!(Montgomery::new(&(i128::MAX - 1)).is_none());
+        assert!(Montgomery::new(&BigInt::from(2).pow(200)).is_none());
+
+        for modulus in [2i64, 4, 1 << 40, i64::MAX - 1] {
+            assert!(!matches!(ModContext::new(&modulus), ModContext::Montgomery(_)), "{}", modulus);
+            check_reducer(&Barrett::new(&modulus).unwrap(), &values(&modulus));
+        }
+        check_reducer(&Barrett::new(&(i128::MAX - 1)).unwrap(), &values(&(i128::MAX - 1)));
+        assert!(matches!(ModContext::new(&1i64), ModContext::Plain(_)));
+        check_reducer(&Plain::new(&1i64), &[0, 1, 5]);
+    }
+}
@@ -0,0 +1,173 @@
+//! values of homogeneous cyclotomic polynomials and the aurifeuillean polynomials that split them
+
+use std::f64::consts::PI;
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::One;
+
+use crate::primality::jacobi_symbol;
+
+/// aurifeuillean polynomials are computed from floating point roots up to this squarefree part,
+/// beyond it the rounding errors of the product of the roots come close to one half
+pub(crate) const AURIFEUILLEAN_BOUND: u64 = 64;
+
+/// returns the divisors of n in increasing order
+pub(crate) fn divisors(n: u64) -> Vec<u64> {
+    let (mut low, mut high) = (vec![], vec![]);
+    let mut divisor = 1;
+    while divisor * divisor <= n {
+        if n.is_multiple_of(divisor) {
+            low.push(divisor);
+            if divisor * divisor != n {
+                high.push(n / divisor);
+            }
+        }
+        divisor += 1;
+    }
+
+    low.extend(high.into_iter().rev());
+    return low;
+}
+
+/// returns the möbius function of n: 0 if a square divides n, else -1 to the number of primes
+fn moebius(mut n: u64) -> i8 {
+    let mut value = 1;
+    let mut prime = 2;
+    while prime * prime <= n {
+        if n.is_multiple_of(prime) {
+            n /= prime;
+            if n.is_multiple_of(prime) {
+                return 0;
+            }
+            value = -value;
+        }
+        prime += 1;
+    }
+
+    match n > 1 {
+        true => -value,
+        false => value,
+    }
+}
+
+/// returns the order of the cyclotomic polynomial a squarefree s > 1 gives an aurifeuillean
+/// factorization of: s for s = 1 mod 4 and 2s otherwise
+pub(crate) fn aurifeuillean_order(s: u64) -> u64 {
+    match s % 4 == 1 {
+        true => s,
+        false => 2 * s,
+    }
+}
+
+/// returns Φ_n(a, b) = b^φ(n)·Φ_n(a/b), the product of (a^d - b^d)^μ(n/d) over the divisors d of
+/// n. a and b have to differ
+pub(crate) fn cyclotomic_value(n: u64, a: &BigInt, b: &BigInt) -> BigInt {
+    let (mut numerator, mut denominator) = (BigInt::one(), BigInt::one());
+    for divisor in divisors(n) {
+        let term = a.pow(divisor as u32) - b.pow(divisor as u32);
+        match moebius(n / divisor) {
+            1 => numerator *= term,
+            -1 => denominator *= term,
+            _ => {}
+        }
+    }
+
+    return numerator / denominator;
+}
+
+/// returns the coefficients of C and D, lowest degree first, with Φ_n(x) = C(x)² - s·x·D(x)² for
+/// a squarefree s > 1 and n = aurifeuillean_order(s). x = s·y² turns this into a difference of
+/// squares. None if s is out of range
+pub(crate) fn aurifeuillean_polynomials(s: u64) -> Option<(Vec<BigInt>, Vec<BigInt>)> {
+    if !(2..=AURIFEUILLEAN_BOUND).contains(&s) || moebius(s) == 0 {
+        return None;
+    }
+
+    // Φ_n(z²/s) splits over Q(√s) into ∏ (z - ω^j) over one class of the roots ω^j of unity of
+    // order 2n, the class is given by the quadratic character of Q(√s), flipped between j and
+    // j + n. the coefficients of z^2i are those of C, the ones of z^(2i+1) are -√s times D's
+    let n = aurifeuillean_order(s);
+    let character = |j: u64| match s % 4 == 1 {
+        true => jacobi_symbol(&(j as i64), &(s as i64)) * if j.is_even() { 1 } else { -1 },
+        false => jacobi_symbol(&(s as i64), &(j as i64)),
+    };
+
+    let mut product = vec![(1.0, 0.0)];
+    for j in (1..2 * n).filter(|j| j.gcd(&n) == 1 && (s % 4 == 1 || j.is_odd()) && character(*j) == 1) {
+        let angle = PI * j as f64 / n as f64;
+        let (real, imaginary) = (angle.cos(), angle.sin());
+
+        // multiplies by (z - ω^j)
+        let mut next = vec![(0.0, 0.0); product.len() + 1];
+        for (degree, (a, b)) in product.iter().enumerate() {
+            next[degree + 1].0 += a;
+            next[degree + 1].1 += b;
+            next[degree].0 -= a * real - b * imaginary;
+            next[degree].1 -= a * imaginary + b * real;
+        }
+        product = next;
+    }
+
+    let root = (s as f64).sqrt();
+    let (mut c, mut d) = (vec![], vec![]);
+    for (degree, (real, _)) in product.iter().enumerate() {
+        let (value, coefficients) = match degree.is_even() {
+            true => (*real, &mut c),
+            false => (-real / root, &mut d),
+        };
+        if (value - value.round()).abs() > 0.25 {
+            return None;
+        }
+        coefficients.push(BigInt::from(value.round() as i64));
+    }
+
+    // both sides have degree φ(n), so agreeing on more points than that proves the identity
+    let verified = (2..product.len() as i64 + 2).map(BigInt::from).all(|x| {
+        let (c_value, d_value) = (evaluate(&c, &x), evaluate(&d, &x));
+        &c_value * &c_value - s * &x * &d_value * &d_value == cyclotomic_value(n, &x, &BigInt::one())
+    });
+    match verified {
+        true => Some((c, d)),
+        false => None,
+    }
+}
+
+/// evaluates a polynomial with horner's rule
+fn evaluate(coefficients: &[BigInt], x: &BigInt) -> BigInt {
+    coefficients.iter().rev().fold(BigInt::ZERO, |value, coefficient| value * x + coefficient)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cyclotomic_values_multiply_to_the_binomial() {
+        assert_eq!(divisors(36), vec![1, 2, 3, 4, 6, 9, 12, 18, 36]);
+        assert_eq!((1..=10).map(moebius).collect::<Vec<_>>(), vec![1, -1, -1, 0, -1, 1, -1, 0, 0, 1]);
+
+        let (a, b) = (BigInt::from(7), BigInt::from(3));
+        for n in 1..60u64 {
+            let product = divisors(n).into_iter().map(|d| cyclotomic_value(d, &a, &b)).product::<BigInt>();
+            assert_eq!(product, a.pow(n as u32) - b.pow(n as u32));
+        }
+        assert_eq!(cyclotomic_value(4, &BigInt::from(2), &BigInt::one()), BigInt::from(5));
+    }
+
+    #[test]
+    fn aurifeuillean_polynomials_of_all_squarefree_parts() {
+        // Φ_4(x) = (x + 1)² - 2x and Φ_5(x) = (x² + 3x + 1)² - 5x(x + 1)²
+        let small = |values: &[i64]| values.iter().copied().map(BigInt::from).collect::<Vec<_>>();
+        let (c, d) = aurifeuillean_polynomials(2).unwrap();
+        assert_eq!((c, d.iter().map(|x| x * x).collect()), (small(&[1, 1]), small(&[1])));
+        let (c, d) = aurifeuillean_polynomials(5).unwrap();
+        assert_eq!((c, d.iter().map(|x| x * x).collect()), (small(&[1, 3, 1]), small(&[1, 1])));
+
+        for s in 2..=AURIFEUILLEAN_BOUND {
+            assert_eq!(aurifeuillean_polynomials(s).is_some(), moebius(s) != 0, "{}", s);
+        }
+        assert_eq!(aurifeuillean_polynomials(AURIFEUILLEAN_BOUND + 1), None);
+    }
+}
@@ -0,0 +1,224 @@
+//! multi-limb helpers on little-endian 64-bit limbs, used by the reducers
+
+use std::cmp::Ordering;
+
+use num_bigint::BigUint;
+
+/// returns the low and high limb of acc + a * b + carry, which can never overflow 128 bits
+pub(crate) fn mac(acc: u64, a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let wide = acc as u128 + a as u128 * b as u128 + carry as u128;
+    (wide as u64, (wide >> 64) as u64)
+}
+
+/// returns a + b + carry and the outgoing carry
+pub(crate) fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let wide = a as u128 + b as u128 + carry as u128;
+    (wide as u64, (wide >> 64) as u64)
+}
+
+/// returns a - b - borrow and the outgoing borrow
+pub(crate) fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let wide = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (wide as u64, (wide >> 127) as u64)
+}
+
+/// adds a * factor onto acc of the same length, returns the carry limb.
+/// dispatches to an architecture specific fast path if the cpu supports one
+pub(crate) fn mac_row(acc: &mut [u64], a: &[u64], factor: u64) -> u64 {
+    #[cfg(target_arch = "x86_64")]
+    if x86_64::has_mulx_adx() {
+        // SAFETY: the required cpu features were detected at runtime
+        return unsafe { x86_64::mac_row(acc, a, factor) };
+    }
+
+    portable_mac_row(acc, a, factor)
+}
+
+/// portable version of mac_row on 128-bit intermediates
+fn portable_mac_row(acc: &mut [u64], a: &[u64], factor: u64) -> u64 {
+    let mut carry = 0;
+    for (limb, &value) in acc.iter_mut().zip(a) {
+        (*limb, carry) = mac(*limb, value, factor, carry);
+    }
+
+    return carry;
+}
+
+/// runs coarsely integrated operand scanning, returns a * b / R mod n for R = 2^(64 * n.len())
+pub(crate) fn montgomery_mul_limbs(a: &[u64], b: &[u64], n: &[u64], n_prime: u64) -> Vec<u64> {
+    let size = n.len();
+    let mut t = vec![0u64; size + 2];
+
+    for &factor in b {
+        // multiply: t += a * b[i]
+        let carry = mac_row(&mut t[..size], a, factor);
+        (t[size], t[size + 1]) = adc(t[size], carry, 0);
+
+        // reduce: t = (t + m * n) / 2^64, chosen such that the lowest limb cancels
+        let m = t[0].wrapping_mul(n_prime);
+        let carry = mac_row(&mut t[..size], n, m);
+        let (sum, overflow) = adc(t[size], carry, 0);
+        (t[size], t[size + 1]) = (sum, t[size + 1] + overflow);
+
+        t.copy_within(1.., 0);
+        t[size + 1] = 0;
+    }
+
+    // the result is below 2n, so subtract n at most once
+    let mut result = t[..size].to_vec();
+    let mut borrow = 0;
+    for j in 0..size {
+        (result[j], borrow) = sbb(t[j], n[j], borrow);
+    }
+    match t[size] == 0 && borrow == 1 {
+        true => t[..size].to_vec(),
+        false => result,
+    }
+}
+
+/// multiplies two limb slices with the schoolbook method
+pub(crate) fn mul_limbs(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut result = vec![0u64; a.len() + b.len()];
+
+    for (i, &factor) in b.iter().enumerate() {
+        result[i + a.len()] = mac_row(&mut result[i..i + a.len()], a, factor);
+    }
+
+    return result;
+}
+
+/// subtracts b from a modulo 2^(64 * a.len()), exact whenever a >= b
+pub(crate) fn sub_limbs(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut result = vec![0u64; a.len()];
+
+    let mut borrow = 0;
+    for (i, &limb) in a.iter().enumerate() {
+        (result[i], borrow) = sbb(limb, *b.get(i).unwrap_or(&0), borrow);
+    }
+
+    return result;
+}
+
+/// compares two limb slices of possibly different lengths
+pub(crate) fn cmp_limbs(a: &[u64], b: &[u64]) -> Ordering {
+    let size = a.len().max(b.len());
+
+    (0..size).rev()
+        .map(|i| a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// returns the number of significant bits of a limb slice
+pub(crate) fn bits_limbs(a: &[u64]) -> u64 {
+    match a.iter().rposition(|&limb| limb != 0) {
+        Some(i) => 64 * i as u64 + 64 - a[i].leading_zeros() as u64,
+        None => 0,
+    }
+}
+
+/// converts little-endian 64-bit limbs into an unsigned big integer
+pub(crate) fn biguint_from_limbs(limbs: &[u64]) -> BigUint {
+    let digits = limbs.iter().flat_map(|&limb| [limb as u32, (limb >> 32) as u32]).collect();
+    BigUint::new(digits)
+}
+
+
+// architecture fast paths
+#[cfg(target_arch = "x86_64")]
+mod x86_64 {
+    use std::arch::x86_64::{_addcarryx_u64, _mulx_u64};
+
+    /// returns whether the cpu supports mulx (bmi2) and adcx/adox (adx)
+    pub fn has_mulx_adx() -> bool {
+        is_x86_feature_detected!("bmi2") && is_x86_feature_detected!("adx")
+    }
+
+    /// adds a * factor onto acc with mulx, keeping the low and high halves of the
+    /// products in two independent carry chains
+    #[target_feature(enable = "bmi2,adx")]
+    pub unsafe fn mac_row(acc: &mut [u64], a: &[u64], factor: u64) -> u64 {
+        let (mut carry_low, mut carry_high) = (0u8, 0u8);
+        let mut previous_high = 0u64;
+
+        for (limb, &value) in acc.iter_mut().zip(a) {
+            let mut high = 0u64;
+            let low = _mulx_u64(value, factor, &mut high);
+
+            let (mut sum, mut result) = (0u64, 0u64);
+            carry_low = _addcarryx_u64(carry_low, *limb, low, &mut sum);
+            carry_high = _addcarryx_u64(carry_high, sum, previous_high, &mut result);
+
+            *limb = result;
+            previous_high = high;
+        }
+
+        // acc + a * factor fits one more limb, so this cannot overflow
+        previous_high + carry_low as u64 + carry_high as u64
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn mac_row_matches_portable() {
+        let mut rng = rand::thread_rng();
+
+        for size in [1, 2, 3, 4, 8, 17, 64] {
+            for _ in 0..100 {
+                let a: Vec<u64> = (0..size).map(|_| rng.gen()).collect();
+                let acc: Vec<u64> = (0..size).map(|_| rng.gen()).collect();
+                let factor = match rng.gen_bool(0.1) {
+                    true => u64::MAX,
+                    false => rng.gen(),
+                };
+
+                let mut expected = acc.clone();
+                let expected_carry = portable_mac_row(&mut expected, &a, factor);
+                let mut actual = acc.clone();
+                let actual_carry = mac_row(&mut actual, &a, factor);
+
+                assert_eq!((actual, actual_carry), (expected, expected_carry));
+            }
+        }
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn x86_64_mac_row_matches_portable() {
+        if !x86_64::has_mulx_adx() {
+            return;
+        }
+        let mut rng = rand::thread_rng();
+
+        for size in [1, 2, 4, 9, 32] {
+            for _ in 0..100 {
+                let a: Vec<u64> = (0..size).map(|_| rng.gen()).collect();
+                let mut expected = vec![u64::MAX; size];
+                let mut actual = expected.clone();
+
+                let expected_carry = portable_mac_row(&mut expected, &a, u64::MAX);
+                let actual_carry = unsafe { x86_64::mac_row(&mut actual, &a, u64::MAX) };
+
+                assert_eq!((actual, actual_carry), (expected, expected_carry));
+            }
+        }
+    }
+
+    #[test]
+    fn mul_limbs_matches_bigint() {
+        let mut rng = rand::thread_rng();
+
+        for (size_a, size_b) in [(1, 1), (2, 3), (4, 4), (7, 2), (16, 16)] {
+            let a: Vec<u64> = (0..size_a).map(|_| rng.gen()).collect();
+            let b: Vec<u64> = (0..size_b).map(|_| rng.gen()).collect();
+
+            let expected = biguint_from_limbs(&a) * biguint_from_limbs(&b);
+            assert_eq!(biguint_from_limbs(&mul_limbs(&a, &b)), expected);
+        }
+    }
+}
@@ -0,0 +1,369 @@
+//! number types and the modular arithmetic all curves and factorizations are built on
+
+use std::fmt::{Debug, Display};
+
+use num_bigint::{BigInt, RandBigInt};
+use num_integer::{Integer, Roots};
+use num_traits::Signed;
+use rand::Rng;
+
+use crate::error::{Error, Result};
+
+pub mod context;
+pub(crate) mod cyclotomic;
+mod limbs;
+pub(crate) mod polynomial;
+mod roots;
+
+pub use context::{Barrett, ModContext, Montgomery, Plain, PseudoMersenne, Reducer};
+pub use roots::{exact_root, is_perfect_power};
+
+
+// number types
+/// integer type all arithmetic, curves and the factorization are generic over
+pub trait Number: Clone + Debug + Display + Ord + From<i64> + Integer + Signed + Roots {
+    /// returns the position of the most significant bit of the absolute value
+    fn msb_position(&self) -> u64;
+
+    /// returns whether the bit at `index` of the absolute value is set
+    fn bit(&self, index: u64) -> bool;
+
+    /// returns a random number in the range 0..bound
+    fn random_below<R: Rng + ?Sized>(rng: &mut R, bound: &Self) -> Self;
+
+    /// returns the little-endian 64-bit limbs of the absolute value
+    fn to_limbs(&self) -> Vec<u64>;
+
+    /// builds a non-negative number from little-endian 64-bit limbs
+    fn from_limbs(limbs: &[u64]) -> Self;
+
+    /// multiplies two reduced numbers in 0..modulo without overflowing the type.
+    /// the default runs double_and_add, so only sums of reduced values are formed
+    fn mul_mod(&self, other: &Self, modulo: &Self) -> Self {
+        // switch values to increase performance with large factors and small bases
+        let (base, factor) = match other > self {
+            true => (other, self),
+            false => (self, other)
+        };
+
+        // get the position of the most significant bit and run algorithm
+        let msb = factor.msb_position();
+        let mut result = Self::zero();
+
+        for index in (0..=msb).rev() {
+            // double
+            result = add_mod(&result, &result, modulo);
+
+            // add
+            if factor.bit(index) {
+                result = add_mod(&result, base, modulo);
+            }
+        }
+
+        return result;
+    }
+}
+
+macro_rules! impl_primitive_number {
+    ($int:ty $(, $($extra:tt)*)?) => {
+        impl Number for $int {
+            fn msb_position(&self) -> u64 {
+                get_msb_position(*self as i128) as u64
+            }
+
+            fn bit(&self, index: u64) -> bool {
+                (self.unsigned_abs() >> index) & 0b1 == 1
+            }
+
+            fn random_below<R: Rng + ?Sized>(rng: &mut R, bound: &Self) -> Self {
+                rng.gen_range(0..*bound)
+            }
+
+            fn to_limbs(&self) -> Vec<u64> {
+                let value = self.unsigned_abs() as u128;
+                (0..<$int>::BITS / 64).map(|index| (value >> (64 * index)) as u64).collect()
+            }
+
+            fn from_limbs(limbs: &[u64]) -> Self {
+                limbs.iter().rev().fold(0u128, |value, &limb| value.wrapping_shl(64) | limb as u128) as $int
+            }
+
+            $($($extra)*)?
+        }
+    };
+}
+
+impl_primitive_number!(i64,
+    fn mul_mod(&self, other: &Self, modulo: &Self) -> Self {
+        // widening multiplication, the product of two reduced i64 always fits into i128
+        (*self as i128 * *other as i128).rem_euclid(*modulo as i128) as i64
+    }
+);
+impl_primitive_number!(i128);
+
+impl Number for BigInt {
+    fn msb_position(&self) -> u64 {
+        self.bits().saturating_sub(1)
+    }
+
+    fn bit(&self, index: u64) -> bool {
+        self.magnitude().bit(index)
+    }
+
+    fn random_below<R: Rng + ?Sized>(rng: &mut R, bound: &Self) -> Self {
+        rng.gen_bigint_range(&BigInt::from(0), bound)
+    }
+
+    fn to_limbs(&self) -> Vec<u64> {
+        self.magnitude().to_u64_digits()
+    }
+
+    fn from_limbs(limbs: &[u64]) -> Self {
+        BigInt::from(limbs::biguint_from_limbs(limbs))
+    }
+
+    fn mul_mod(&self, other: &Self, modulo: &Self) -> Self {
+        // arbitrary precision cannot overflow, so multiply directly
+        (self * other).mod_floor(modulo)
+    }
+}
+
+
+// helper functions
+/// returns the position of the most significant bit of a number, 0 for zero.
+/// only relies on leading_zeros, so it is portable to every architecture
+pub fn get_msb_position(number: i128) -> u8 {
+    (i128::BITS - 1).saturating_sub(number.unsigned_abs().leading_zeros()) as u8
+}
+
+/// runs the square_and_multiply algorithm for exponentiation.
+/// panics if the modulo is not positive or the exponent is negative
+pub fn mod_pow<T: Number>(base: &T, exponent: &T, modulo: &T) -> T {
+    checked_mod_pow(base, exponent, modulo).expect("mod_pow requires modulo > 0 and exponent >= 0")
+}
+
+/// runs the square_and_multiply algorithm, returns InvalidInput for inputs out of range
+pub fn checked_mod_pow<T: Number>(base: &T, exponent: &T, modulo: &T) -> Result<T, T> {
+    if !modulo.is_positive() {
+        return Err(Error::InvalidInput("modulo must be positive"));
+    }
+    if exponent.is_negative() {
+        return Err(Error::InvalidInput("exponent must not be negative"));
+    }
+
+    // get the position of the most significant bit and run algorithm
+    let base = base.mod_floor(modulo);
+    let msb = exponent.msb_position();
+    let mut result = T::one().mod_floor(modulo);
+
+    for index in (0..=msb).rev() {
+        // square
+        result = result.mul_mod(&result, modulo);
+
+        // multiply
+        if exponent.bit(index) {
+            result = result.mul_mod(&base, modulo);
+        }
+    }
+
+    return Ok(result);
+}
+
+/// multiplies two numbers without overflowing for any modulo that fits the type.
+/// panics if the modulo is not positive
+pub fn mod_mul<T: Number>(base: &T, factor: &T, modulo: &T) -> T {
+    checked_mod_mul(base, factor, modulo).expect("mod_mul requires modulo > 0")
+}
+
+/// multiplies two numbers, returns InvalidInput for inputs out of range
+pub fn checked_mod_mul<T: Number>(base: &T, factor: &T, modulo: &T) -> Result<T, T> {
+    if !modulo.is_positive() {
+        return Err(Error::InvalidInput("modulo must be positive"));
+    }
+
+    Ok(base.mod_floor(modulo).mul_mod(&factor.mod_floor(modulo), modulo))
+}
+
+/// adds two reduced numbers in 0..modulo without overflowing
+pub fn add_mod<T: Number>(number1: &T, number2: &T, modulo: &T) -> T {
+    // compare against the distance to modulo instead of forming the sum
+    let distance = modulo.clone() - number2.clone();
+
+    match *number1 >= distance {
+        true => number1.clone() - distance,
+        false => number1.clone() + number2.clone()
+    }
+}
+
+/// subtracts two reduced numbers in 0..modulo without overflowing
+pub fn sub_mod<T: Number>(number1: &T, number2: &T, modulo: &T) -> T {
+    match number1 >= number2 {
+        true => number1.clone() - number2.clone(),
+        false => number1.clone() + (modulo.clone() - number2.clone())
+    }
+}
+
+/// returns the modular inverse of a number, or the divisor it shares with the modulo
+pub fn mod_inv<T: Number>(number: &T, modulo: &T) -> Result<T, T> {
+    if !modulo.is_positive() {
+        return Err(Error::InvalidInput("modulo must be positive"));
+    }
+
+    let (g, result, _) = euclid_gcd(number.mod_floor(modulo), modulo.clone());
+
+    match g.is_one() {
+        true => Ok(result.mod_floor(modulo)),
+        false => Err(Error::NotInvertible { gcd: g }),
+    }
+}
+
+/// returns a square root of a number modulo a prime with the tonelli-shanks algorithm, None if
+/// the number is no square modulo the prime
+pub fn sqrt_mod<T: Number>(number: &T, prime: &T) -> Option<T> {
+    let residue = number.mod_floor(prime);
+    if residue.is_zero() || *prime == T::from(2) {
+        return Some(residue);
+    }
+
+    // euler's criterion, a^((p - 1)/2) = 1 for the squares
+    let half = (prime.clone() - T::one()).div_floor(&T::from(2));
+    if !mod_pow(&residue, &half, prime).is_one() {
+        return None;
+    }
+
+    // p - 1 = q·2^s with odd q
+    let (mut odd, mut shift) = (prime.clone() - T::one(), 0u32);
+    while odd.is_even() {
+        odd = odd.div_floor(&T::from(2));
+        shift += 1;
+    }
+
+    let mut non_residue = T::from(2);
+    while mod_pow(&non_residue, &half, prime).is_one() {
+        non_residue = non_residue + T::one();
+    }
+
+    // keeps r² = a·t with t of order 2^m, every step halves the order of t
+    let mut c = mod_pow(&non_residue, &odd, prime);
+    let mut t = mod_pow(&residue, &odd, prime);
+    let mut root = mod_pow(&residue, &(odd + T::one()).div_floor(&T::from(2)), prime);
+    while !t.is_one() {
+        let (mut order, mut square) = (0, t.clone());
+        while !square.is_one() {
+            square = mod_mul(&square, &square, prime);
+            order += 1;
+        }
+
+        let mut b = c;
+        for _ in 0..shift - order - 1 {
+            b = mod_mul(&b, &b, prime);
+        }
+        shift = order;
+        c = mod_mul(&b, &b, prime);
+        t = mod_mul(&t, &c, prime);
+        root = mod_mul(&root, &b, prime);
+    }
+
+    Some(root)
+}
+
+/// interface for euclidean gcd
+pub fn gcd<T: Number>(number1: &T, number2: &T) -> T {
+    euclid_gcd(number1.clone(), number2.clone()).0
+}
+
+/// executes euclidean gcd
+fn euclid_gcd<T: Number>(number1: T, number2: T) -> (T, T, T) {
+    match number1.is_zero() {
+        true => { (number2, T::zero(), T::one()) }
+        false => {
+            let (g, x, y) = euclid_gcd(number2.mod_floor(&number1), number1.clone());
+            (g, y - (number2.div_floor(&number1)) * x.clone(), x)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn msb_position_covers_all_bits() {
+        assert_eq!(get_msb_position(0), 0);
+        assert_eq!(get_msb_position(1), 0);
+        assert_eq!(get_msb_position(-8), 3);
+        assert_eq!(get_msb_position(1 << 64), 64);
+        assert_eq!(get_msb_position(u64::MAX as i128), 63);
+        assert_eq!(get_msb_position(i128::MAX), 126);
+        assert_eq!(get_msb_position(i128::MIN), 127);
+
+        for shift in 0..127 {
+            assert_eq!(get_msb_position(1 << shift), shift as u8);
+            assert_eq!((1i128 << shift).msb_position(), shift as u64);
+            assert_eq!(BigInt::from(1i128 << shift).msb_position(), shift as u64);
+        }
+    }
+
+    #[test]
+    fn primitives_do_not_overflow_near_the_type_limits() {
+        let mut rng = rand::thread_rng();
+        let big = |number: i128| BigInt::from(number);
+
+        // the largest odd and even moduli of both types, 2^63 - 25 and 2^127 - 1 are prime
+        let moduli = [i64::MAX as i128, i64::MAX as i128 - 1, 9_223_372_036_854_775_783, i128::MAX, i128::MAX - 1];
+        for modulo in moduli {
+            for _ in 0..200 {
+                let (a, b) = (rng.gen_range(0..modulo), rng.gen_range(0..modulo));
+                let exponent = rng.gen_range(0..i128::MAX);
+                let m = big(modulo);
+
+                assert_eq!(big(add_mod(&a, &b, &modulo)), (big(a) + big(b)) % &m);
+                assert_eq!(big(sub_mod(&a, &b, &modulo)), (big(a) - big(b)).mod_floor(&m));
+                assert_eq!(big(mod_mul(&a, &b, &modulo)), big(a) * big(b) % &m);
+                assert_eq!(big(mod_pow(&a, &exponent, &modulo)), big(a).modpow(&big(exponent), &m));
+
+                if modulo <= i64::MAX as i128 {
+                    let (a, b, modulo) = (a as i64, b as i64, modulo as i64);
+                    assert_eq!(big(add_mod(&a, &b, &modulo) as i128), (big(a as i128) + big(b as i128)) % &m);
+                    assert_eq!(big(mod_mul(&a, &b, &modulo) as i128), big(a as i128) * big(b as i128) % &m);
+                    assert_eq!(big(mod_pow(&a, &(exponent as i64).abs(), &modulo) as i128), big(a as i128).modpow(&big((exponent as i64).abs() as i128), &m));
+                }
+                if let Ok(inverse) = mod_inv(&a, &modulo) {
+                    assert_eq!(big(inverse) * big(a) % &m, BigInt::from(1) % &m);
+                }
+            }
+        }
+
+        // negative operands are reduced first, the results stay in range
+        assert_eq!(mod_mul(&-3i128, &(i128::MAX - 1), &i128::MAX), 3);
+        assert_eq!(mod_pow(&-1i64, &i64::MAX, &i64::MAX), i64::MAX - 1);
+
+        // out of range inputs are reported instead of overflowing or looping
+        assert_eq!(checked_mod_pow(&2i64, &-1, &7), Err(Error::InvalidInput("exponent must not be negative")));
+        for modulo in [0i128, -7, i128::MIN] {
+            assert_eq!(checked_mod_pow(&2, &3, &modulo), Err(Error::InvalidInput("modulo must be positive")));
+            assert_eq!(checked_mod_mul(&2, &3, &modulo), Err(Error::InvalidInput("modulo must be positive")));
+            assert_eq!(mod_inv(&2, &modulo), Err(Error::InvalidInput("modulo must be positive")));
+        }
+    }
+
+    #[test]
+    fn sqrt_mod_finds_the_roots_of_all_squares() {
+        for prime in [2i64, 3, 5, 13, 17, 97, 257, 65_537, 1_000_003] {
+            for number in 0..prime.min(3_000) {
+                match sqrt_mod(&number, &prime) {
+                    Some(root) => assert_eq!(root * root % prime, number),
+                    None => assert_eq!(mod_pow(&number, &((prime - 1) / 2), &prime), prime - 1),
+                }
+            }
+        }
+
+        // 2^127 - 1 = 1 mod 2^k only for k = 1, a prime with p = 1 mod 2^20 needs every step
+        for prime in [BigInt::from(2).pow(127) - 1, BigInt::from(7) * BigInt::from(2).pow(20) + 1] {
+            let square = BigInt::from(1_234_567).pow(2) % &prime;
+            let root = sqrt_mod(&square, &prime).unwrap();
+            assert_eq!(&root * &root % &prime, square);
+        }
+    }
+}
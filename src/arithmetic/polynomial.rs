@@ -0,0 +1,271 @@
+//! dense polynomials over the integers modulo n, multiplied by kronecker substitution
+
+use num_bigint::BigUint;
+
+use super::limbs::{bits_limbs, biguint_from_limbs};
+use super::{add_mod, sub_mod, Number};
+
+/// dense polynomial with residues in 0..n as coefficients, lowest degree first.
+/// the zero polynomial has no coefficients
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Polynomial<T: Number> {
+    pub(crate) coefficients: Vec<T>,
+}
+
+impl<T: Number> Polynomial<T> {
+    /// creates a polynomial from its coefficients, dropping leading zeros
+    pub(crate) fn new(coefficients: Vec<T>) -> Self {
+        let mut polynomial = Polynomial { coefficients };
+        polynomial.trim();
+        return polynomial;
+    }
+
+    /// returns the degree, 0 for constants and the zero polynomial
+    pub(crate) fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    /// returns the monic polynomial (X - r1)(X - r2)··· with the given roots
+    pub(crate) fn from_roots(roots: &[T], modulus: &T) -> Self {
+        ProductTree::new(roots, modulus).root()
+    }
+
+    /// multiplies two polynomials by packing the coefficients into one big integer each, so
+    /// the product runs on the fast integer multiplication
+    pub(crate) fn mul(&self, other: &Polynomial<T>, modulus: &T) -> Polynomial<T> {
+        if self.coefficients.is_empty() || other.coefficients.is_empty() {
+            return Polynomial { coefficients: vec![] };
+        }
+
+        // every coefficient of the integer product is below min(len)·n², so it fits its slot
+        let length = self.coefficients.len().min(other.coefficients.len()) as u64;
+        let bits = 2 * bits_limbs(&modulus.to_limbs()) + 64 - length.leading_zeros() as u64;
+        let slot = bits.div_ceil(64) as usize;
+
+        let product = pack(&self.coefficients, slot) * pack(&other.coefficients, slot);
+        let modulus = biguint_from_limbs(&modulus.to_limbs());
+        let mut limbs = product.to_u64_digits();
+        limbs.resize((self.coefficients.len() + other.coefficients.len() - 1) * slot, 0);
+
+        let coefficients = limbs.chunks(slot)
+            .map(|chunk| T::from_limbs(&(biguint_from_limbs(chunk) % &modulus).to_u64_digits()))
+            .collect();
+        Polynomial::new(coefficients)
+    }
+
+    /// returns the remainder of the division by a monic polynomial. the quotient is taken from
+    /// the reversed polynomials with a newton inverse, so no coefficient is divided
+    pub(crate) fn rem(&self, divisor: &Polynomial<T>, modulus: &T) -> Polynomial<T> {
+        let degree = divisor.degree();
+        if self.coefficients.len() <= degree {
+            return self.clone();
+        }
+
+        // reversing turns the quotient into the low coefficients of rev(a) / rev(b)
+        let length = self.coefficients.len() - degree;
+        let reversed = Polynomial::new(self.coefficients.iter().rev().take(length).cloned().collect());
+        let inverse = divisor.reversed().inverse(length, modulus);
+        let mut quotient = reversed.mul(&inverse, modulus).truncated(length).coefficients;
+        quotient.resize(length, T::zero());
+        quotient.reverse();
+
+        let product = Polynomial::new(quotient).mul(divisor, modulus);
+        let remainder = self.coefficients.iter().take(degree).enumerate()
+            .map(|(index, coefficient)| sub_mod(coefficient, product.coefficients.get(index).unwrap_or(&T::zero()), modulus))
+            .collect();
+        Polynomial::new(remainder)
+    }
+
+    /// evaluates the polynomial at many points with a remainder tree, the remainders modulo
+    /// X - r are the values at r
+    pub(crate) fn evaluate(&self, points: &[T], modulus: &T) -> Vec<T> {
+        ProductTree::new(points, modulus).remainders(self, modulus).into_iter()
+            .map(|remainder| remainder.coefficients.first().cloned().unwrap_or(T::zero()))
+            .collect()
+    }
+
+    /// returns the inverse modulo X^length of a polynomial with constant coefficient one,
+    /// each newton step g = g·(2 - f·g) doubles the correct coefficients
+    fn inverse(&self, length: usize, modulus: &T) -> Polynomial<T> {
+        let two = Polynomial::new(vec![add_mod(&T::one(), &T::one(), modulus)]);
+        let mut inverse = Polynomial::new(vec![T::one()]);
+        let mut precision = 1;
+
+        while precision < length {
+            precision = (2 * precision).min(length);
+            let error = self.truncated(precision).mul(&inverse, modulus).truncated(precision);
+            inverse = inverse.mul(&two.sub(&error, modulus), modulus).truncated(precision);
+        }
+
+        return inverse;
+    }
+
+    /// subtracts two polynomials coefficient-wise
+    fn sub(&self, other: &Polynomial<T>, modulus: &T) -> Polynomial<T> {
+        let zero = T::zero();
+        let length = self.coefficients.len().max(other.coefficients.len());
+
+        Polynomial::new((0..length).map(|index| {
+            sub_mod(self.coefficients.get(index).unwrap_or(&zero), other.coefficients.get(index).unwrap_or(&zero), modulus)
+        }).collect())
+    }
+
+    /// returns the polynomial modulo X^length
+    fn truncated(&self, length: usize) -> Polynomial<T> {
+        Polynomial::new(self.coefficients.iter().take(length).cloned().collect())
+    }
+
+    /// returns X^degree · f(1/X)
+    fn reversed(&self) -> Polynomial<T> {
+        Polynomial::new(self.coefficients.iter().rev().cloned().collect())
+    }
+
+    /// drops leading zero coefficients
+    fn trim(&mut self) {
+        while self.coefficients.last().is_some_and(|coefficient| coefficient.is_zero()) {
+            self.coefficients.pop();
+        }
+    }
+}
+
+/// products of the linear factors X - r, pairwise combined level by level up to the root
+struct ProductTree<T: Number> {
+    levels: Vec<Vec<Polynomial<T>>>,
+}
+
+impl<T: Number> ProductTree<T> {
+    /// builds the tree bottom up from the linear factors
+    fn new(points: &[T], modulus: &T) -> Self {
+        let leaves = points.iter()
+            .map(|point| Polynomial::new(vec![sub_mod(&T::zero(), &point.mod_floor(modulus), modulus), T::one()]))
+            .collect::<Vec<_>>();
+        let mut levels = vec![leaves];
+
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let level = levels.last().unwrap().chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => left.mul(right, modulus),
+                    _ => pair[0].clone(),
+                })
+                .collect();
+            levels.push(level);
+        }
+
+        ProductTree { levels }
+    }
+
+    /// returns the product of all factors, one for no factors
+    fn root(&self) -> Polynomial<T> {
+        match self.levels.last().and_then(|level| level.first()) {
+            Some(root) => root.clone(),
+            None => Polynomial::new(vec![T::one()]),
+        }
+    }
+
+    /// reduces a polynomial top down, returns its remainders modulo the leaves
+    fn remainders(&self, polynomial: &Polynomial<T>, modulus: &T) -> Vec<Polynomial<T>> {
+        let mut remainders = vec![polynomial.clone()];
+
+        for level in self.levels.iter().rev() {
+            remainders = level.iter().enumerate()
+                .map(|(index, node)| remainders[index / 2].rem(node, modulus))
+                .collect();
+        }
+
+        return remainders;
+    }
+}
+
+/// packs coefficients into one integer, each into a slot of the given number of limbs
+fn pack<T: Number>(coefficients: &[T], slot: usize) -> BigUint {
+    let mut limbs = vec![0u64; coefficients.len() * slot];
+
+    for (index, coefficient) in coefficients.iter().enumerate() {
+        for (offset, limb) in coefficient.to_limbs().into_iter().enumerate().take(slot) {
+            limbs[index * slot + offset] = limb;
+        }
+    }
+
+    return biguint_from_limbs(&limbs);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigInt;
+    use rand::Rng;
+
+    use crate::arithmetic::mod_mul;
+
+    /// returns a random polynomial with the given number of coefficients
+    fn random<T: Number>(length: usize, modulus: &T) -> Polynomial<T> {
+        let mut rng = rand::thread_rng();
+        Polynomial::new((0..length).map(|_| T::random_below(&mut rng, modulus)).collect())
+    }
+
+    /// multiplies with the schoolbook method
+    fn schoolbook<T: Number>(a: &Polynomial<T>, b: &Polynomial<T>, modulus: &T) -> Polynomial<T> {
+        let mut product = vec![T::zero(); (a.coefficients.len() + b.coefficients.len()).saturating_sub(1)];
+
+        for (i, x) in a.coefficients.iter().enumerate() {
+            for (j, y) in b.coefficients.iter().enumerate() {
+                product[i + j] = add_mod(&product[i + j], &mod_mul(x, y, modulus), modulus);
+            }
+        }
+
+        Polynomial::new(product)
+    }
+
+    /// divides by a monic polynomial with the schoolbook method, returns the remainder
+    fn long_division<T: Number>(a: &Polynomial<T>, divisor: &Polynomial<T>, modulus: &T) -> Polynomial<T> {
+        let mut remainder = a.coefficients.clone();
+        let degree = divisor.degree();
+
+        while remainder.len() > degree {
+            let (lead, shift) = (remainder.pop().unwrap(), remainder.len() - degree);
+            for (index, coefficient) in divisor.coefficients.iter().take(degree).enumerate() {
+                remainder[shift + index] = sub_mod(&remainder[shift + index], &mod_mul(&lead, coefficient, modulus), modulus);
+            }
+        }
+
+        Polynomial::new(remainder)
+    }
+
+    /// evaluates with the horner scheme
+    fn horner<T: Number>(polynomial: &Polynomial<T>, point: &T, modulus: &T) -> T {
+        polynomial.coefficients.iter().rev()
+            .fold(T::zero(), |value, coefficient| add_mod(&mod_mul(&value, point, modulus), coefficient, modulus))
+    }
+
+    fn check_arithmetic<T: Number>(modulus: T) {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..20 {
+            let (a, b) = (random(rng.gen_range(0..70), &modulus), random(rng.gen_range(0..70), &modulus));
+            assert_eq!(a.mul(&b, &modulus), schoolbook(&a, &b, &modulus));
+
+            // a = q·b + r with deg r < deg b
+            let mut monic = random(rng.gen_range(0..30), &modulus).coefficients;
+            monic.push(T::one());
+            let divisor = Polynomial::new(monic);
+            assert_eq!(a.rem(&divisor, &modulus), long_division(&a, &divisor, &modulus));
+
+            let points: Vec<T> = (0..rng.gen_range(0..40)).map(|_| T::random_below(&mut rng, &modulus)).collect();
+            let values = a.evaluate(&points, &modulus);
+            assert_eq!(values, points.iter().map(|point| horner(&a, point, &modulus)).collect::<Vec<_>>());
+
+            let roots = Polynomial::from_roots(&points, &modulus);
+            assert_eq!(roots.coefficients.len(), points.len() + 1);
+            assert!(roots.evaluate(&points, &modulus).iter().all(|value| value.is_zero()));
+        }
+    }
+
+    #[test]
+    fn kronecker_products_remainders_and_evaluation() {
+        check_arithmetic(1_000_003i64);
+        check_arithmetic(i64::MAX);
+        check_arithmetic((1i128 << 100) - 15);
+        check_arithmetic(BigInt::from(10).pow(60) + 7);
+    }
+}
@@ -0,0 +1,99 @@
+//! exact integer roots and the detection of perfect powers
+
+use super::Number;
+use crate::sieve::Primes;
+
+/// returns the k-th root of a number if it is exact. negative numbers only have odd roots, a
+/// zeroth root does not exist
+pub fn exact_root<T: Number>(number: &T, k: u32) -> Option<T> {
+    if k == 0 || number.is_negative() && k.is_multiple_of(2) {
+        return None;
+    }
+
+    // nth_root rounds towards zero, so the root is exact iff its power gives the number back
+    let root = number.nth_root(k);
+    match num_traits::pow(root.clone(), k as usize) == *number {
+        true => Some(root),
+        false => None,
+    }
+}
+
+/// returns (base, exponent) with base^exponent = number for the largest exponent above one, None
+/// if the number is no perfect power. -1, 0 and 1 are powers of themselves for every exponent
+/// and yield None as well
+pub fn is_perfect_power<T: Number>(number: &T) -> Option<(T, u32)> {
+    if *number >= -T::one() && *number <= T::one() {
+        return None;
+    }
+
+    // every exponent is a product of primes, whose roots are taken as often as they are exact.
+    // a base left after all square roots has no square root after the cube roots either
+    let (mut base, mut exponent) = (number.clone(), 1);
+    for prime in Primes::up_to(number.msb_position()) {
+        if prime > base.msb_position() {
+            break;
+        }
+        while let Some(root) = exact_root(&base, prime as u32) {
+            base = root;
+            exponent *= prime as u32;
+        }
+    }
+
+    match exponent > 1 {
+        true => Some((base, exponent)),
+        false => None,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigInt;
+
+    #[test]
+    fn exact_roots_of_all_types() {
+        for number in -1_000i64..1_000 {
+            assert_eq!(exact_root(&number, 1), Some(number));
+            for k in 2..12u32 {
+                // the positive root of an even power comes first
+                let expected = (0..=32i64).chain(-32..0).find(|root| root.checked_pow(k) == Some(number));
+                assert_eq!(exact_root(&number, k), expected, "{} {}", number, k);
+            }
+        }
+
+        assert_eq!(exact_root(&i64::MIN, 63), Some(-2));
+        assert_eq!(exact_root(&(3i128.pow(80)), 40), Some(9));
+        assert_eq!(exact_root(&(3i128.pow(80) + 1), 40), None);
+        let big = BigInt::from(1_000_003).pow(17);
+        assert_eq!(exact_root(&big, 17), Some(BigInt::from(1_000_003)));
+        assert_eq!(exact_root(&(big - 1), 17), None);
+        assert_eq!(exact_root(&5i64, 0), None);
+    }
+
+    #[test]
+    fn perfect_powers_take_the_largest_exponent() {
+        // brute force over all bases and exponents
+        let mut powers = std::collections::HashMap::new();
+        for base in 2..320i64 {
+            let mut power = base * base;
+            let mut exponent = 2;
+            while power < 100_000 {
+                powers.entry(power).or_insert((base, exponent));
+                power *= base;
+                exponent += 1;
+            }
+        }
+        for number in 2..100_000i64 {
+            assert_eq!(is_perfect_power(&number), powers.get(&number).copied(), "{}", number);
+        }
+
+        assert_eq!(is_perfect_power(&-64i64), Some((-4, 3)));
+        assert_eq!(is_perfect_power(&-4i64), None);
+        assert_eq!(is_perfect_power(&1i64), None);
+        assert_eq!(is_perfect_power(&i64::MIN), Some((-2, 63)));
+        assert_eq!(is_perfect_power(&(6i128.pow(30))), Some((6, 30)));
+        assert_eq!(is_perfect_power(&(BigInt::from(10).pow(60))), Some((BigInt::from(10), 60)));
+        assert_eq!(is_perfect_power(&(BigInt::from(10).pow(60) + 1)), None);
+    }
+}
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use crate::arithmetic::{ModContext, Number, Reducer};
+use crate::error::{Error, Result};
+
+/// twisted edwards curve a·x² + y² = 1 + d·x²·y² over the integers modulo p
+#[derive(Clone)]
+pub struct TwistedEdwards<T: Number> {
+    pub(crate) a: T,
+    pub(crate) d: T,
+    pub(crate) d2: T,
+    pub(crate) a_is_minus_one: bool,
+    pub(crate) p: T,
+    pub(crate) context: Arc<ModContext<T>>,
+}
+
+impl<T: Number> TwistedEdwards<T> {
+    /// creates a curve, fails if it is singular or p is below two
+    pub fn new(a: T, d: T, p: T) -> Result<Self, T> {
+        if p <= T::one() {
+            return Err(Error::InvalidInput("p must be at least two"));
+        }
+
+        TwistedEdwards::with_context(a, d, Arc::new(ModContext::new(&p)))
+    }
+
+    /// creates a curve on an existing context, so curves over the same modulus share it
+    pub fn with_context(a: T, d: T, context: Arc<ModContext<T>>) -> Result<Self, T> {
+        let p = context.modulus().clone();
+        if p <= T::one() {
+            return Err(Error::InvalidInput("p must be at least two"));
+        }
+
+        // a·d·(a - d), computed in the form of the context
+        let (a, d) = (context.encode(&a), context.encode(&d));
+        let discriminant = context.mul(&context.mul(&a, &d), &context.sub(&a, &d));
+        if discriminant.is_zero() {
+            return Err(Error::SingularCurve);
+        }
+
+        // a = -1 allows the faster addition, which needs 2d
+        let a_is_minus_one = a == context.neg(&context.one());
+        let d2 = context.add(&d, &d);
+
+        Ok(TwistedEdwards { a, d, d2, a_is_minus_one, p, context })
+    }
+
+    /// returns the coefficient a
+    pub fn a(&self) -> T {
+        self.context.decode(&self.a)
+    }
+
+    /// returns the coefficient d
+    pub fn d(&self) -> T {
+        self.context.decode(&self.d)
+    }
+
+    /// returns the modulus p
+    pub fn p(&self) -> &T {
+        &self.p
+    }
+}
+
+impl<T: Number> PartialEq for TwistedEdwards<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.a == other.a && self.d == other.d && self.p == other.p
+    }
+}
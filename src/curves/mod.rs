@@ -0,0 +1,9 @@
+//! elliptic curves over the integers modulo p
+
+mod edwards;
+mod montgomery;
+mod weierstrass;
+
+pub use edwards::TwistedEdwards;
+pub use montgomery::MontgomeryCurve;
+pub use weierstrass::WeierStrass;
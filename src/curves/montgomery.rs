@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use crate::arithmetic::{ModContext, Number, Reducer};
+use crate::error::{Error, Result};
+
+/// montgomery curve B·y² = x³ + A·x² + x over the integers modulo p
+#[derive(Clone)]
+pub struct MontgomeryCurve<T: Number> {
+    pub(crate) a: T,
+    pub(crate) b: T,
+    pub(crate) a24: T,
+    pub(crate) p: T,
+    pub(crate) context: Arc<ModContext<T>>,
+}
+
+impl<T: Number> MontgomeryCurve<T> {
+    /// creates a curve, fails if it is singular, p is below two or 4 is not invertible
+    pub fn new(a: T, b: T, p: T) -> Result<Self, T> {
+        if p <= T::one() {
+            return Err(Error::InvalidInput("p must be at least two"));
+        }
+
+        MontgomeryCurve::with_context(a, b, Arc::new(ModContext::new(&p)))
+    }
+
+    /// creates a curve on an existing context, so curves over the same modulus share it
+    pub fn with_context(a: T, b: T, context: Arc<ModContext<T>>) -> Result<Self, T> {
+        let p = context.modulus().clone();
+        if p <= T::one() {
+            return Err(Error::InvalidInput("p must be at least two"));
+        }
+
+        // B(A² - 4), computed in the form of the context
+        let (a, b) = (context.encode(&a), context.encode(&b));
+        let four = context.encode(&T::from(4));
+        let discriminant = context.mul(&b, &context.sub(&context.square(&a), &four));
+        if discriminant.is_zero() {
+            return Err(Error::SingularCurve);
+        }
+
+        // (A + 2) / 4 is the only constant the x-only doubling needs
+        let a24 = context.mul(&context.add(&a, &context.encode(&T::from(2))), &context.inv(&four)?);
+
+        Ok(MontgomeryCurve { a, b, a24, p, context })
+    }
+
+    /// returns the coefficient A
+    pub fn a(&self) -> T {
+        self.context.decode(&self.a)
+    }
+
+    /// returns the coefficient B
+    pub fn b(&self) -> T {
+        self.context.decode(&self.b)
+    }
+
+    /// returns the modulus p
+    pub fn p(&self) -> &T {
+        &self.p
+    }
+}
+
+impl<T: Number> PartialEq for MontgomeryCurve<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.a == other.a && self.b == other.b && self.p == other.p
+    }
+}
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+
+use crate::arithmetic::{ModContext, Number, Reducer};
+use crate::error::{Error, Result};
+
+/// short weierstrass curve y² = x³ + ax + b over the integers modulo p
+#[derive(Clone)]
+pub struct WeierStrass<T: Number> {
+    pub(crate) a: T,
+    pub(crate) b: T,
+    pub(crate) p: T,
+    pub(crate) context: Arc<ModContext<T>>,
+}
+
+impl<T: Number> WeierStrass<T> {
+    /// creates a curve, fails if it is singular or p is below two
+    pub fn new(a: T, b: T, p: T) -> Result<Self, T> {
+        if p <= T::one() {
+            return Err(Error::InvalidInput("p must be at least two"));
+        }
+
+        WeierStrass::with_context(a, b, Arc::new(ModContext::new(&p)))
+    }
+
+    /// creates a curve on an existing context, so curves over the same modulus share it
+    pub fn with_context(a: T, b: T, context: Arc<ModContext<T>>) -> Result<Self, T> {
+        let p = context.modulus().clone();
+        if p <= T::one() {
+            return Err(Error::InvalidInput("p must be at least two"));
+        }
+
+        // 4a³ + 27b², computed in the form of the context
+        let (a, b) = (context.encode(&a), context.encode(&b));
+        let discriminant = context.add(
+            &context.mul(&context.encode(&T::from(4)), &context.pow(&a, &T::from(3))),
+            &context.mul(&context.encode(&T::from(27)), &context.square(&b)),
+        );
+
+        match discriminant.is_zero() {
+            true => Err(Error::SingularCurve),
+            false => Ok(WeierStrass { a, b, p, context })
+        }
+    }
+
+    /// returns the coefficient a
+    pub fn a(&self) -> T {
+        self.context.decode(&self.a)
+    }
+
+    /// returns the coefficient b
+    pub fn b(&self) -> T {
+        self.context.decode(&self.b)
+    }
+
+    /// returns the modulus p
+    pub fn p(&self) -> &T {
+        &self.p
+    }
+}
+
+impl<T: Number> PartialEq for WeierStrass<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.a == other.a && self.b == other.b && self.p == other.p
+    }
+}
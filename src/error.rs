@@ -0,0 +1,38 @@
+//! error type shared by the arithmetic, the curves and the factorization
+
+use std::{error, fmt};
+
+/// reasons an operation can fail, generic over the number type so a found gcd is kept
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error<T> {
+    /// the discriminant 4a³ + 27b² vanishes modulo p
+    SingularCurve,
+    /// the operands lie on different curves
+    CurveMismatch,
+    /// the number shares the divisor gcd with the modulus, so it has no inverse
+    NotInvertible { gcd: T },
+    /// the input is prime, so there is nothing to factorize
+    InputIsPrime,
+    /// no factor was found within the allowed number of curves
+    IterationLimitReached,
+    /// an argument is out of the allowed range
+    InvalidInput(&'static str),
+}
+
+/// result type with the error of this crate
+pub type Result<V, T> = std::result::Result<V, Error<T>>;
+
+impl<T: fmt::Display> fmt::Display for Error<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::SingularCurve => write!(formatter, "the curve is singular"),
+            Error::CurveMismatch => write!(formatter, "the points lie on different curves"),
+            Error::NotInvertible { gcd } => write!(formatter, "not invertible, shares the divisor {} with the modulus", gcd),
+            Error::InputIsPrime => write!(formatter, "the input is prime"),
+            Error::IterationLimitReached => write!(formatter, "no factor found within the iteration limit"),
+            Error::InvalidInput(reason) => write!(formatter, "invalid input: {}", reason),
+        }
+    }
+}
+
+impl<T: fmt::Debug + fmt::Display> error::Error for Error<T> {}
@@ -0,0 +1,219 @@
+//! parser for integer expressions like 2^521-1 or (10^71-1)/9
+
+use std::iter::Peekable;
+use std::str::{Chars, FromStr};
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{ToPrimitive, Zero};
+
+use crate::error::{Error, Result};
+
+/// powers are limited to this many bits, so a typo like 10^10^10 fails instead of running out
+/// of memory
+const MAX_POWER_BITS: u64 = 1 << 24;
+
+/// integer expression over decimal numbers with the operators + - * / ^ and parentheses. the
+/// tree is kept, so special forms like a^n - b^n can be recognized before it is evaluated
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expression {
+    /// a decimal literal
+    Number(BigInt),
+    /// -x
+    Negate(Box<Expression>),
+    /// x + y
+    Add(Box<Expression>, Box<Expression>),
+    /// x - y
+    Subtract(Box<Expression>, Box<Expression>),
+    /// x · y
+    Multiply(Box<Expression>, Box<Expression>),
+    /// x / y, which has to be exact
+    Divide(Box<Expression>, Box<Expression>),
+    /// x^y for a non-negative 32 bit y
+    Power(Box<Expression>, Box<Expression>),
+}
+
+impl Expression {
+    /// evaluates the expression, fails with InvalidInput on an inexact division, a division by
+    /// zero or an exponent out of range
+    pub fn evaluate(&self) -> Result<BigInt, BigInt> {
+        let value = match self {
+            Expression::Number(number) => number.clone(),
+            Expression::Negate(operand) => -operand.evaluate()?,
+            Expression::Add(left, right) => left.evaluate()? + right.evaluate()?,
+            Expression::Subtract(left, right) => left.evaluate()? - right.evaluate()?,
+            Expression::Multiply(left, right) => left.evaluate()? * right.evaluate()?,
+            Expression::Divide(left, right) => {
+                let (dividend, divisor) = (left.evaluate()?, right.evaluate()?);
+                if divisor.is_zero() {
+                    return Err(Error::InvalidInput("division by zero"));
+                }
+                let (quotient, remainder) = dividend.div_rem(&divisor);
+                if !remainder.is_zero() {
+                    return Err(Error::InvalidInput("the division is not exact"));
+                }
+                quotient
+            }
+            Expression::Power(base, exponent) => {
+                let base = base.evaluate()?;
+                let exponent = exponent.evaluate()?.to_u32()
+                    .ok_or(Error::InvalidInput("exponent must be a non-negative 32 bit integer"))?;
+                if base.bits() * exponent as u64 > MAX_POWER_BITS {
+                    return Err(Error::InvalidInput("the power is too large"));
+                }
+                base.pow(exponent)
+            }
+        };
+
+        Ok(value)
+    }
+}
+
+impl FromStr for Expression {
+    type Err = Error<BigInt>;
+
+    /// parses with the usual precedence: sums of products of powers. powers are right
+    /// associative and a leading sign applies to the power after it, so -2^2 is -4
+    fn from_str(input: &str) -> Result<Self, BigInt> {
+        let mut parser = Parser { characters: input.chars().peekable() };
+        let expression = parser.sum()?;
+
+        match parser.next_symbol() {
+            None => Ok(expression),
+            Some(_) => Err(Error::InvalidInput("unexpected character")),
+        }
+    }
+}
+
+/// recursive descent parser with one function per precedence level
+struct Parser<'a> {
+    characters: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    /// sum = product (('+' | '-') product)*
+    fn sum(&mut self) -> Result<Expression, BigInt> {
+        let mut expression = self.product()?;
+        loop {
+            expression = match self.peek_symbol() {
+                Some('+') => Expression::Add(self.skip(expression), Box::new(self.product()?)),
+                Some('-') => Expression::Subtract(self.skip(expression), Box::new(self.product()?)),
+                _ => return Ok(expression),
+            };
+        }
+    }
+
+    /// product = signed (('*' | '/') signed)*
+    fn product(&mut self) -> Result<Expression, BigInt> {
+        let mut expression = self.signed()?;
+        loop {
+            expression = match self.peek_symbol() {
+                Some('*') => Expression::Multiply(self.skip(expression), Box::new(self.signed()?)),
+                Some('/') => Expression::Divide(self.skip(expression), Box::new(self.signed()?)),
+                _ => return Ok(expression),
+            };
+        }
+    }
+
+    /// signed = ('-' | '+') signed | power
+    fn signed(&mut self) -> Result<Expression, BigInt> {
+        match self.peek_symbol() {
+            Some('-') => {
+                self.characters.next();
+                Ok(Expression::Negate(Box::new(self.signed()?)))
+            }
+            Some('+') => {
+                self.characters.next();
+                self.signed()
+            }
+            _ => self.power(),
+        }
+    }
+
+    /// power = atom ('^' signed)?
+    fn power(&mut self) -> Result<Expression, BigInt> {
+        let base = self.atom()?;
+        match self.peek_symbol() {
+            Some('^') => Ok(Expression::Power(self.skip(base), Box::new(self.signed()?))),
+            _ => Ok(base),
+        }
+    }
+
+    /// atom = number | '(' sum ')'
+    fn atom(&mut self) -> Result<Expression, BigInt> {
+        match self.peek_symbol() {
+            Some('(') => {
+                self.characters.next();
+                let expression = self.sum()?;
+                match self.next_symbol() {
+                    Some(')') => Ok(expression),
+                    _ => Err(Error::InvalidInput("missing closing parenthesis")),
+                }
+            }
+            Some(digit) if digit.is_ascii_digit() => {
+                let mut digits = String::new();
+                while let Some(digit) = self.characters.next_if(char::is_ascii_digit) {
+                    digits.push(digit);
+                }
+                Ok(Expression::Number(digits.parse().unwrap()))
+            }
+            _ => Err(Error::InvalidInput("expected a number or an opening parenthesis")),
+        }
+    }
+
+    /// returns the next character after whitespace without consuming it
+    fn peek_symbol(&mut self) -> Option<char> {
+        while self.characters.next_if(|character| character.is_whitespace()).is_some() {}
+        self.characters.peek().copied()
+    }
+
+    /// consumes the next character after whitespace
+    fn next_symbol(&mut self) -> Option<char> {
+        self.peek_symbol();
+        self.characters.next()
+    }
+
+    /// consumes the operator that was peeked and boxes the operand before it
+    fn skip(&mut self, expression: Expression) -> Box<Expression> {
+        self.characters.next();
+        Box::new(expression)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// parses and evaluates an expression
+    fn value(input: &str) -> Result<BigInt, BigInt> {
+        input.parse::<Expression>()?.evaluate()
+    }
+
+    #[test]
+    fn precedence_and_associativity() {
+        assert_eq!(value("1 + 2 * 3").unwrap(), BigInt::from(7));
+        assert_eq!(value("(1 + 2) * 3").unwrap(), BigInt::from(9));
+        assert_eq!(value("10 - 4 - 3").unwrap(), BigInt::from(3));
+        assert_eq!(value("2^3^2").unwrap(), BigInt::from(512));
+        assert_eq!(value("-2^2").unwrap(), BigInt::from(-4));
+        assert_eq!(value("(-2)^3 + +1").unwrap(), BigInt::from(-7));
+        assert_eq!(value("(10^71-1)/9").unwrap(), "1".repeat(71).parse::<BigInt>().unwrap());
+        assert_eq!(value("2^521-1").unwrap(), BigInt::from(2).pow(521) - 1);
+
+        assert_eq!(
+            "2^3-1".parse::<Expression>().unwrap(),
+            Expression::Subtract(
+                Box::new(Expression::Power(Box::new(Expression::Number(BigInt::from(2))), Box::new(Expression::Number(BigInt::from(3))))),
+                Box::new(Expression::Number(BigInt::from(1))),
+            ),
+        );
+    }
+
+    #[test]
+    fn malformed_expressions_fail() {
+        for input in ["", "2^", "(1 + 2", "1 + 2)", "3 $ 4", "2 3", "7/2", "1/0", "2^-1", "10^10^10"] {
+            assert!(matches!(value(input), Err(Error::InvalidInput(_))), "{}", input);
+        }
+    }
+}
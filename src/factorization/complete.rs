@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+
+use rand::Rng;
+
+use crate::arithmetic::{is_perfect_power, Number};
+use crate::error::{Error, Result};
+use crate::factorization::lenstra::{ecm, ecm_curve, smooth_order, EcmConfig};
+use crate::factorization::rho::pollard_rho;
+use crate::factorization::siqs::siqs;
+use crate::factorization::small::small_factor;
+use crate::factorization::trial_division::trial_division;
+use crate::primality::is_prime;
+
+/// composites below 2^64 are split by the word-sized methods, which skip all big integer overhead
+const SMALL_BITS: u64 = 64;
+/// composites below 2^70 are split by pollard's rho instead of the curves
+const RHO_BITS: u64 = 70;
+/// composites from 2^100 on, about 30 digits, go to the quadratic sieve, which beats the curves
+/// from there on unless a factor is much smaller than the square root
+const SIQS_BITS: u64 = 100;
+/// curves tried before the sieve, a few catch the factors far below the square root while
+/// the sieve outruns any longer curve run on balanced factors
+const SIQS_CURVES: usize = 4;
+
+/// factorizes a number completely into primes, returns the sorted pairs (prime, exponent).
+/// a negative number starts with (-1, 1), one has no factors and zero fails with InvalidInput
+pub fn factor_completely<T: Number>(number: T) -> Result<Vec<(T, u32)>, T> {
+    factor_completely_with(number, &EcmConfig::default())
+}
+
+/// factorizes a number completely with the given settings of the elliptic curve method
+pub fn factor_completely_with<T: Number>(number: T, config: &EcmConfig) -> Result<Vec<(T, u32)>, T> {
+    if number.is_zero() {
+        return Err(Error::InvalidInput("zero has no prime factorization"));
+    }
+
+    let mut primes = BTreeMap::new();
+    if number.is_negative() {
+        primes.insert(-T::one(), 1);
+    }
+
+    // small primes first, so every cofactor left for the curves is odd. two is always divided
+    // out, the curves need an odd number
+    let (small, remaining) = trial_division(&number, config.trial_division_bound.max(2));
+    primes.extend(small);
+
+    // split the composites with their multiplicity until only primes are left
+    let mut composites = vec![(remaining, 1)];
+    while let Some((composite, multiplicity)) = composites.pop() {
+        if composite.is_one() {
+            continue;
+        }
+        if is_prime(&composite) {
+            *primes.entry(composite).or_insert(0) += multiplicity;
+            continue;
+        }
+
+        // the curves find no factor of a prime power, so roots are taken first
+        if let Some((root, exponent)) = is_perfect_power(&composite) {
+            composites.push((root, multiplicity * exponent));
+            continue;
+        }
+
+        let factor = split(&composite, config)?;
+        composites.push((composite.div_floor(&factor), multiplicity));
+        composites.push((factor, multiplicity));
+    }
+
+    Ok(primes.into_iter().collect())
+}
+
+/// splits an odd composite that is no perfect power. word-sized ones go to SQUFOF and friends, rho
+/// is faster than the curves up to about 21 digits. above p - 1 and p + 1 run first, then the
+/// curves, which the quadratic sieve takes over from after a few tries for large composites.
+/// the curves also take over whenever another method gives up
+fn split<T: Number>(composite: &T, config: &EcmConfig) -> Result<T, T> {
+    if composite.msb_position() < SMALL_BITS {
+        let word = composite.to_limbs().first().copied().unwrap_or(0);
+        if let Some(factor) = small_factor(word) {
+            return Ok(T::from_limbs(&[factor]));
+        }
+    }
+
+    if composite.msb_position() < RHO_BITS {
+        if let Ok(factor) = pollard_rho(composite) {
+            return Ok(factor);
+        }
+    } else if let Some(factor) = smooth_order(composite, config) {
+        return Ok(factor);
+    }
+
+    if composite.msb_position() >= SIQS_BITS {
+        // a few curves catch factors far below the square root, which the sieve cannot exploit
+        let mut rng = rand::thread_rng();
+        for _ in 0..SIQS_CURVES {
+            let sigma = T::from(rng.gen_range(6..1 << 31));
+            if let Ok(Some(factor)) = ecm_curve(composite, &sigma, config) {
+                return Ok(factor);
+            }
+        }
+
+        if let Ok(factor) = siqs(composite) {
+            return Ok(factor);
+        }
+    }
+
+    ecm(composite, config).map(|found| found.factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigInt;
+
+    /// factorizes by trial division
+    fn trial_division(number: i64) -> Vec<(i64, u32)> {
+        let mut factors = vec![];
+        let mut remaining = number;
+
+        for divisor in 2..=number {
+            let mut exponent = 0;
+            while remaining % divisor == 0 {
+                remaining /= divisor;
+                exponent += 1;
+            }
+            if exponent > 0 {
+                factors.push((divisor, exponent));
+            }
+        }
+
+        return factors;
+    }
+
+    #[test]
+    fn small_numbers_match_trial_division() {
+        for number in 1..3_000i64 {
+            assert_eq!(factor_completely(number).unwrap(), trial_division(number));
+        }
+        assert_eq!(factor_completely(-12i64).unwrap(), vec![(-1, 1), (2, 2), (3, 1)]);
+        assert_eq!(factor_completely(-1i64).unwrap(), vec![(-1, 1)]);
+        assert!(matches!(factor_completely(0i64), Err(Error::InvalidInput(_))));
+    }
+
+    #[test]
+    fn primes_powers_and_large_cofactors() {
+        let (p, q) = (1_000_003i128, 1_000_033i128);
+
+        assert_eq!(factor_completely(q).unwrap(), vec![(q, 1)]);
+        assert_eq!(factor_completely(p * p * q * q * q).unwrap(), vec![(p, 2), (q, 3)]);
+        assert_eq!(factor_completely(3i128.pow(40)).unwrap(), vec![(3, 40)]);
+        assert_eq!(factor_completely(p.pow(6)).unwrap(), vec![(p, 6)]);
+        assert_eq!(factor_completely(2 * 7 * p * p * q).unwrap(), vec![(2, 1), (7, 1), (p, 2), (q, 1)]);
+
+        let big = BigInt::from(p).pow(3) * BigInt::from(q) * BigInt::from(1_000_037);
+        let expected = vec![(BigInt::from(p), 3), (BigInt::from(q), 1), (BigInt::from(1_000_037), 1)];
+        assert_eq!(factor_completely(big).unwrap(), expected);
+
+        // 30 digits with two balanced primes, which the quadratic sieve splits
+        let (p, q) = (BigInt::from(822_795_090_203_071i64), BigInt::from(296_184_619_168_531i64));
+        assert_eq!(factor_completely(&p * &q).unwrap(), vec![(q, 1), (p, 1)]);
+    }
+
+    #[test]
+    fn forty_digits_go_to_the_quadratic_sieve() {
+        // p - 1 and p + 1 of both primes have a large factor, so neither p - 1 nor p + 1 help
+        let p = BigInt::from(10_000_000_000_000_000_087u128);
+        let q = BigInt::from(100_000_000_000_000_000_039u128);
+        let n = &p * &q;
+        assert_eq!(n.to_string().len(), 40);
+        assert!(n.bits() >= SIQS_BITS);
+        assert_eq!(factor_completely(n).unwrap(), vec![(p, 1), (q, 1)]);
+    }
+}
@@ -0,0 +1,373 @@
+use std::sync::Arc;
+
+use num_bigint::BigInt;
+use num_traits::One;
+use rand::Rng;
+
+use crate::arithmetic::{gcd, is_perfect_power, ModContext, Number, Reducer};
+use crate::error::{Error, Result};
+use crate::factorization::pm1::pollard_pm1;
+use crate::factorization::pp1::williams_pp1;
+use crate::factorization::stage_two::{Continuation, StageTwo};
+use crate::factorization::trial_division::trial_division;
+use crate::points::{ChudnovskyPoint, EdwardsPoint, MontgomeryPoint, WeierStrassPoint};
+use crate::primality::is_prime;
+use crate::sieve::Primes;
+
+const DEFAULT_B1: u64 = 2_000;
+const DEFAULT_B2_FACTOR: u64 = 100;
+const DEFAULT_TRIAL_DIVISION_BOUND: u64 = 10_000;
+const MAX_ITERATIONS: u32 = 10_000;
+/// p - 1 runs with ten times and p + 1 with three times the B1 of the curves, so each costs
+/// about as much as three curves
+const PM1_B1_FACTOR: u64 = 10;
+const PP1_B1_FACTOR: u64 = 3;
+
+/// curve model and arithmetic stage 1 runs on
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Engine {
+    /// suyama's curves in short weierstrass form with double_and_add in chudnovsky coordinates
+    WeierStrass,
+    /// suyama's montgomery curves with the x-only montgomery ladder
+    #[default]
+    Montgomery,
+    /// a = -1 twisted edwards curves of the z/2 × z/4 torsion family in extended coordinates,
+    /// which take the faster addition, where sigma is the parameter m of the family
+    Edwards,    /// twisted edwards curves of the z/12 torsion family in extended coordinates, whose larger
+    /// torsion makes the group order smooth more often than z/2 × z/4 at the cost of the general
+    /// addition, where sigma is the parameter k of the family
+    EdwardsZ12,
+}
+
+/// algorithm stage 2 runs with
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StageTwoMethod {
+    /// pairing for small B2 with few primes to compare, polynomial evaluation for larger ones
+    #[default]
+    Auto,
+    /// baby-step giant-step continuation that only compares the pairs covering a prime
+    Pairing,
+    /// fast polynomial continuation with product and remainder trees, practical up to B2 ~ 10^12
+    Polynomial,
+}
+
+/// factor found by the elliptic curve method, with the sigma of the curve that found it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EcmFactor<T> {
+    /// proper divisor of the number
+    pub factor: T,
+    /// parameter of the curve, which ecm_curve turns into the same curve again
+    pub sigma: T,
+}
+
+/// settings of the lenstra factorization
+#[derive(Clone, Debug)]
+pub struct EcmConfig {
+    /// curve model and arithmetic of stage 1
+    pub engine: Engine,
+    /// stage 1 bound, every prime up to B1 is multiplied in with its largest power up to B1
+    pub b1: u64,
+    /// stage 2 bound, one more prime up to B2 is caught. None takes 100·B1, a bound up to B1
+    /// skips stage 2
+    pub b2: Option<u64>,
+    /// algorithm of stage 2
+    pub stage_two: StageTwoMethod,
+    /// primes up to this bound are divided out before any curve is tried
+    pub trial_division_bound: u64,
+}
+
+impl Default for EcmConfig {
+    fn default() -> Self {
+        EcmConfig {
+            engine: Engine::default(),
+            b1: DEFAULT_B1,
+            b2: None,
+            stage_two: StageTwoMethod::default(),
+            trial_division_bound: DEFAULT_TRIAL_DIVISION_BOUND,
+        }
+    }
+}
+
+/// work shared by all curves of a run, which only depends on the bounds
+struct Stages {
+    scalar: BigInt,
+    stage_two: Option<StageTwo>,
+}
+
+impl Stages {
+    /// precomputes the stage 1 scalar and the stage 2 pairs, fails for B1 below two
+    fn new<T: Number>(config: &EcmConfig) -> Result<Self, T> {
+        if config.b1 < 2 {
+            return Err(Error::InvalidInput("B1 must be at least two"));
+        }
+
+        let b2 = config.b2.unwrap_or(config.b1.saturating_mul(DEFAULT_B2_FACTOR));
+        Ok(Stages {
+            scalar: stage_one_scalar(config.b1),
+            stage_two: (b2 > config.b1).then(|| StageTwo::new(config.b1, b2, config.stage_two)),
+        })
+    }
+}
+
+impl<T: Number> WeierStrassPoint<T> {
+    /// runs stage 1 of the lenstra algorithm with the scalar lcm(1..=B1) in chudnovsky coordinates
+    /// without any inversion, a single gcd of Z with p at the end reveals the factor
+    fn lenstra(&self, stages: &Stages) -> Option<T> {
+        let point = ChudnovskyPoint::from_affine(self).multiply(&stages.scalar);
+
+        continue_curve(&point, &point.z, stages)
+    }
+}
+
+impl<T: Number> MontgomeryPoint<T> {
+    /// runs stage 1 of the lenstra algorithm with the montgomery ladder, which needs
+    /// no inversion either, so a single gcd of Z with p at the end reveals the factor
+    fn lenstra(&self, stages: &Stages) -> Option<T> {
+        let point = self.ladder(&stages.scalar);
+
+        continue_curve(&point, &point.z, stages)
+    }
+}
+
+impl<T: Number> EdwardsPoint<T> {
+    /// runs stage 1 of the lenstra algorithm on a twisted edwards curve. the neutral
+    /// element is (0, 1), so a single gcd of X with p at the end reveals the factor
+    fn lenstra(&self, stages: &Stages) -> Option<T> {
+        let point = self.multiply(&stages.scalar);
+
+        continue_curve(&point, &point.x, stages)
+    }
+}
+
+/// takes the gcd of the stage 1 coordinate, which vanishes modulo every prime divisor the
+/// point reached the neutral element for, and continues with stage 2 if it is one
+fn continue_curve<T: Number, P: Continuation<T>>(point: &P, coordinate: &T, stages: &Stages) -> Option<T> {
+    let context = point.context();
+    let factor = gcd(&context.decode(coordinate), context.modulus());
+
+    // the neutral element modulo every prime divisor leaves nothing for stage 2
+    if factor > T::one() {
+        return non_trivial_divisor(&factor, context.modulus());
+    }
+
+    let stage_two = stages.stage_two.as_ref()?;
+    non_trivial_divisor(&stage_two.run(point), context.modulus())
+}
+
+/// returns gcd(value, p) if it is a proper divisor of p
+pub(crate) fn non_trivial_divisor<T: Number>(value: &T, p: &T) -> Option<T> {
+    let factor = gcd(value, p);
+
+    // avoid returning p or 1
+    return match *p > factor && factor > T::one() {
+        true => { Some(factor) }
+        false => { None }
+    };
+}
+
+/// returns lcm(1..=b1), the product of the largest power up to b1 of every prime up to b1
+fn stage_one_scalar(b1: u64) -> BigInt {
+    prime_powers(b1).fold(BigInt::one(), |scalar, power| scalar * power)
+}
+
+/// iterates over the largest power up to b1 of every prime up to b1, the factors of the stage 1
+/// scalar shared by the curves, p - 1 and p + 1
+pub(crate) fn prime_powers(b1: u64) -> impl Iterator<Item = u64> {
+    Primes::up_to(b1).map(move |prime| {
+        let mut power = prime;
+        while power <= b1 / prime {
+            power *= prime;
+        }
+        power
+    })
+}
+
+/// runs both stages with the precomputed work on the curve the engine derives from sigma.
+/// a failed inversion while building the curve reveals a factor as well
+fn run_curve<T: Number>(sigma: &T, stages: &Stages, context: &Arc<ModContext<T>>, config: &EcmConfig) -> Option<T> {
+    let point = match config.engine {
+        Engine::WeierStrass => WeierStrassPoint::with_suyama_sigma(sigma, context.clone()).map(|point| point.lenstra(stages)),
+        Engine::Montgomery => MontgomeryPoint::with_suyama_sigma(sigma, context.clone()).map(|point| point.lenstra(stages)),
+        Engine::Edwards => EdwardsPoint::with_z2z4_torsion(sigma, context.clone()).map(|point| point.lenstra(stages)),
+        Engine::EdwardsZ12 => EdwardsPoint::with_z12_torsion(sigma, context.clone()).map(|point| point.lenstra(stages)),
+    };
+
+    match point {
+        Ok(factor) => factor,
+        Err(Error::NotInvertible { gcd }) => non_trivial_divisor(&gcd, context.modulus()),
+        Err(_) => None,
+    }
+}
+
+/// runs the lenstra-factorization algorithm for a provided number
+pub fn factorize<T: Number>(number: T) -> Result<T, T> {
+    factorize_with(number, &EcmConfig::default())
+}
+
+/// runs the lenstra-factorization algorithm with the given settings
+pub fn factorize_with<T: Number>(number: T, config: &EcmConfig) -> Result<T, T> {
+    if number < T::from(2) {
+        return Err(Error::InvalidInput("number must be at least two"));
+    }
+    if is_prime(&number) {
+        return Err(Error::InputIsPrime);
+    }
+
+    // check for dividable by two
+    if number.is_even() {
+        return Ok(T::from(2));
+    }
+
+    // small factors are found by division before any curve work starts
+    if let Some((prime, _)) = trial_division(&number, config.trial_division_bound).0.into_iter().next() {
+        return Ok(prime);
+    }
+
+    // the curves find no factor of a prime power, the base of a perfect power is one already
+    if let Some((base, _)) = is_perfect_power(&number) {
+        return Ok(base);
+    }
+
+    if let Some(factor) = smooth_order(&number, config) {
+        return Ok(factor);
+    }
+
+    ecm(&number, config).map(|found| found.factor)
+}
+
+/// runs p - 1 and p + 1 on an odd composite, they are cheaper than the curves and find every
+/// prime with smooth p - 1 or p + 1 for the bounds derived from the curves
+pub(crate) fn smooth_order<T: Number>(number: &T, config: &EcmConfig) -> Option<T> {
+    let b1 = config.b1.saturating_mul(PM1_B1_FACTOR);
+    if let Ok(Some(factor)) = pollard_pm1(number, b1, b1.saturating_mul(DEFAULT_B2_FACTOR)) {
+        return Some(factor);
+    }
+
+    let b1 = config.b1.saturating_mul(PP1_B1_FACTOR);
+    williams_pp1(number, b1, b1.saturating_mul(DEFAULT_B2_FACTOR)).ok().flatten()
+}
+
+/// runs the elliptic curve method on curves with random sigma until one finds a factor of
+/// an odd composite, returns the factor together with the sigma of that curve
+pub fn ecm<T: Number>(number: &T, config: &EcmConfig) -> Result<EcmFactor<T>, T> {
+    if *number < T::from(2) {
+        return Err(Error::InvalidInput("number must be at least two"));
+    }
+    if is_prime(number) {
+        return Err(Error::InputIsPrime);
+    }
+    if number.is_even() {
+        return Err(Error::InvalidInput("number must be odd"));
+    }
+
+    // the stage 1 scalar and the stage 2 pairs only depend on the bounds, so every curve shares them
+    let stages = Stages::new(config)?;
+    let mut rng = rand::thread_rng();
+    let context = Arc::new(ModContext::new(number));
+    for _ in 0..MAX_ITERATIONS {
+        let sigma = T::from(rng.gen_range(6..1 << 31));
+
+        if let Some(factor) = run_curve(&sigma, &stages, &context, config) {
+            return Ok(EcmFactor { factor, sigma });
+        }
+    }
+
+    return Err(Error::IterationLimitReached);
+}
+
+/// runs the elliptic curve method on the single curve selected by sigma, so a curve that
+/// found a factor can be reproduced exactly from the number, sigma and the bounds. returns None
+/// if the curve finds no factor
+pub fn ecm_curve<T: Number>(number: &T, sigma: &T, config: &EcmConfig) -> Result<Option<T>, T> {
+    if *number <= T::one() {
+        return Err(Error::InvalidInput("number must be at least two"));
+    }
+
+    Ok(run_curve(sigma, &Stages::new(config)?, &Arc::new(ModContext::new(number)), config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stage_one_scalar_is_lcm_of_all_numbers_up_to_b1() {
+        let mut lcm = BigInt::one();
+
+        for b1 in 2..200u64 {
+            lcm = num_integer::Integer::lcm(&lcm, &BigInt::from(b1));
+            assert_eq!(stage_one_scalar(b1), lcm);
+        }
+    }
+
+    #[test]
+    fn stage_two_catches_one_prime_above_b1() {
+        let n = 1_000_003i64 * 1_000_033;
+
+        for engine in [Engine::WeierStrass, Engine::Montgomery, Engine::Edwards, Engine::EdwardsZ12] {
+            let found = |b2: Option<u64>, stage_two: StageTwoMethod| {
+                let config = EcmConfig { engine, b1: 50, b2, stage_two, ..EcmConfig::default() };
+                (6..50i64).filter(|sigma| ecm_curve(&n, sigma, &config).unwrap().is_some()).collect::<Vec<_>>()
+            };
+
+            // a curve only fails in stage 2 if it catches both primes at once
+            let only_stage_one = found(Some(0), StageTwoMethod::Auto);
+            for stage_two in [StageTwoMethod::Pairing, StageTwoMethod::Polynomial] {
+                let with_stage_two = found(None, stage_two);
+                assert!(only_stage_one.iter().all(|sigma| with_stage_two.contains(sigma)));
+                assert!(with_stage_two.len() > 2 * only_stage_one.len());
+            }
+        }
+    }
+
+    #[test]
+    fn edwards_engine_runs_on_a_minus_one_curves() {
+        let n = 1_000_003i64 * 1_000_033;
+        let config = EcmConfig { engine: Engine::Edwards, b1: 50, ..EcmConfig::default() };
+        let (stages, context) = (Stages::new::<i64>(&config).unwrap(), Arc::new(ModContext::new(&n)));
+
+        for sigma in 6..50i64 {
+            let point = EdwardsPoint::with_z2z4_torsion(&sigma, context.clone()).unwrap();
+            assert!(point.curve.a_is_minus_one);
+            assert_eq!(ecm_curve(&n, &sigma, &config).unwrap(), point.lenstra(&stages));
+        }
+    }
+
+    #[test]
+    fn z12_engine_runs_on_z12_curves() {
+        let n = 1_000_003i64 * 1_000_033;
+        let config = EcmConfig { engine: Engine::EdwardsZ12, b1: 50, ..EcmConfig::default() };
+        let (stages, context) = (Stages::new::<i64>(&config).unwrap(), Arc::new(ModContext::new(&n)));
+
+        for sigma in 2..40i64 {
+            let point = EdwardsPoint::with_z12_torsion(&sigma, context.clone()).unwrap();
+            assert_eq!(ecm_curve(&n, &sigma, &config).unwrap(), point.lenstra(&stages));
+        }
+    }
+
+    #[test]
+    fn primes_and_even_numbers_short_circuit() {
+        for prime in [2i128, 3, 1_000_003, i128::MAX] {
+            assert!(matches!(factorize(prime), Err(Error::InputIsPrime)));
+        }
+        assert!(matches!(ecm(&1_000_003i64, &EcmConfig::default()), Err(Error::InputIsPrime)));
+        assert_eq!(factorize(2 * 1_000_003i64).unwrap(), 2);
+        assert_eq!(factorize(9_973 * 1_000_003i64).unwrap(), 9_973);
+        assert_eq!(factorize(1_000_003i64.pow(3)).unwrap(), 1_000_003);
+        assert!(matches!(factorize(1i64), Err(Error::InvalidInput(_))));
+    }
+
+    #[test]
+    fn every_engine_finds_a_factor() {
+        let (p, q) = (1_000_003i64, 1_000_033i64);
+
+        for engine in [Engine::WeierStrass, Engine::Montgomery, Engine::Edwards, Engine::EdwardsZ12] {
+            let config = EcmConfig { engine, ..EcmConfig::default() };
+            let found = ecm(&(p * q), &config).unwrap();
+            assert!(found.factor == p || found.factor == q);
+
+            // the sigma reproduces the curve and with it the factor
+            assert_eq!(ecm_curve(&(p * q), &found.sigma, &config).unwrap(), Some(found.factor));
+        }
+    }
+}
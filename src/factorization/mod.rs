@@ -0,0 +1,22 @@
+//! factorization of integers
+
+mod complete;
+mod lenstra;
+mod pm1;
+mod pp1;
+mod rho;
+mod siqs;
+mod small;
+mod special;
+mod stage_two;
+mod trial_division;
+
+pub use complete::{factor_completely, factor_completely_with};
+pub use lenstra::{ecm, ecm_curve, factorize, factorize_with, EcmConfig, EcmFactor, Engine, StageTwoMethod};
+pub use pm1::pollard_pm1;
+pub use pp1::williams_pp1;
+pub use rho::pollard_rho;
+pub use siqs::siqs;
+pub use small::{hart_olf, lehman, small_factor, squfof};
+pub use special::{factor_expression, factor_expression_with, SpecialForm};
+pub use trial_division::trial_division;
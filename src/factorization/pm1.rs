@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use crate::arithmetic::{ModContext, Number, Reducer};
+use crate::error::{Error, Result};
+use crate::factorization::lenstra::{non_trivial_divisor, prime_powers, StageTwoMethod};
+use crate::factorization::pp1::{smooth_order_factor, LucasPoint};
+use crate::factorization::stage_two::StageTwo;
+use crate::primality::is_prime;
+
+/// base of the powers, any base coprime to the number works
+const BASE: i64 = 3;
+
+/// runs pollard's p - 1 method with the bounds B1 and B2 on an odd composite. it finds a prime
+/// p if p - 1 is B1-smooth up to one more prime up to B2, returns None otherwise. a B2 up to B1
+/// skips stage 2
+pub fn pollard_pm1<T: Number>(number: &T, b1: u64, b2: u64) -> Result<Option<T>, T> {
+    if *number < T::from(2) {
+        return Err(Error::InvalidInput("number must be at least two"));
+    }
+    if is_prime(number) {
+        return Err(Error::InputIsPrime);
+    }
+    if number.is_even() {
+        return Err(Error::InvalidInput("number must be odd"));
+    }
+    if b1 < 2 {
+        return Err(Error::InvalidInput("B1 must be at least two"));
+    }
+
+    let context = Arc::new(ModContext::new(number));
+    let mut power = context.encode(&T::from(BASE).mod_floor(number));
+    for exponent in prime_powers(b1) {
+        power = context.pow(&power, &T::from(exponent as i64));
+    }
+
+    // x + 1/x turns the power into V_k of the lucas sequence, whose continuation compares
+    // x^(m·D) with both x^j and x^(-j). x + 1/x - 2 = (x - 1)²/x, so stage 1 is checked the same way
+    let inverse = match context.inv(&power) {
+        Ok(inverse) => inverse,
+        Err(Error::NotInvertible { gcd }) => return Ok(non_trivial_divisor(&gcd, number)),
+        Err(error) => return Err(error),
+    };
+    let point = LucasPoint::new(context.add(&power, &inverse), context.clone());
+
+    let stage_two = (b2 > b1).then(|| StageTwo::new(b1, b2, StageTwoMethod::Auto));
+    Ok(smooth_order_factor(&point, stage_two.as_ref()))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigInt;
+
+    #[test]
+    fn pm1_finds_primes_with_smooth_p_minus_one() {
+        // p - 1 = 2^2·29·37·233 while p + 1 = 2·3·13·12_821, q - 1 and q + 1 have large factors
+        let (p, q) = (1_000_037i64, 1_000_171i64);
+        assert_eq!(pollard_pm1(&(p * q), 300, 0).unwrap(), Some(p));
+        assert_eq!(pollard_pm1(&(p * q), 100, 0).unwrap(), None);
+        assert_eq!(pollard_pm1(&(p * q), 100, 300).unwrap(), Some(p));
+
+        let big = BigInt::from(10).pow(30) + 99;
+        assert_eq!(pollard_pm1(&(&big * p), 100, 300).unwrap(), Some(BigInt::from(p)));
+        assert!(matches!(pollard_pm1(&big, 100, 300), Err(Error::InputIsPrime)));
+        assert!(matches!(pollard_pm1(&(2 * q), 100, 300), Err(Error::InvalidInput(_))));
+    }
+}
@@ -0,0 +1,181 @@
+use std::sync::Arc;
+
+use crate::arithmetic::{ModContext, Number, Reducer};
+use crate::error::{Error, Result};
+use crate::factorization::lenstra::{non_trivial_divisor, prime_powers, StageTwoMethod};
+use crate::factorization::stage_two::{Continuation, StageTwo};
+use crate::primality::is_prime;
+
+/// starting values P = a/b, (P² - 4 / p) = -1 holds for half of the primes. 2/7 and 6/5 add the
+/// factors 6 and 4 to the group order, which makes it smooth more often
+const SEEDS: [(i64, i64); 3] = [(2, 7), (6, 5), (3, 1)];
+
+/// element V_k = α^k + α^(-k) of the lucas sequence V(P), which behaves like the multiple k·Q of a
+/// point Q with x-coordinate P. V_k only knows α up to inversion, just as x only knows ±Q
+#[derive(Clone, Debug)]
+pub(crate) struct LucasPoint<T: Number> {
+    pub(crate) v: T,
+    pub(crate) context: Arc<ModContext<T>>,
+}
+
+impl<T: Number> LucasPoint<T> {
+    /// creates the element from an encoded V_1
+    pub(crate) fn new(v: T, context: Arc<ModContext<T>>) -> Self {
+        LucasPoint { v, context }
+    }
+
+    /// returns V_km from V_k with the lucas ladder, which keeps V_(i+1) - V_i = V_1 just like the
+    /// montgomery ladder, V_2i = V_i² - 2 and V_2i+1 = V_i·V_i+1 - V_1
+    pub(crate) fn ladder<S: Number>(&self, scalar: &S) -> LucasPoint<T> {
+        let c = &self.context;
+        let two = c.add(&c.one(), &c.one());
+        if scalar.is_zero() {
+            return LucasPoint::new(two, c.clone());
+        }
+
+        let mut low = self.v.clone();
+        let mut high = c.sub(&c.square(&self.v), &two);
+
+        for index in (0..scalar.msb_position()).rev() {
+            let sum = c.sub(&c.mul(&low, &high), &self.v);
+            match scalar.bit(index) {
+                true => {
+                    low = sum;
+                    high = c.sub(&c.square(&high), &two);
+                }
+                false => {
+                    high = sum;
+                    low = c.sub(&c.square(&low), &two);
+                }
+            }
+        }
+
+        return LucasPoint::new(low, c.clone());
+    }
+}
+
+impl<T: Number> Continuation<T> for LucasPoint<T> {
+    fn progression(&self, start: u64, step: u64, count: usize) -> Vec<(T, T)> {
+        // V_(k+step) = V_k·V_step - V_(k-step), the lucas counterpart of the differential addition
+        let c = &self.context;
+        let step_value = self.ladder(&(step as i64)).v;
+        let mut previous = self.ladder(&(start as i64)).v;
+        let mut current = self.ladder(&((start + step) as i64)).v;
+        let mut fractions = vec![];
+
+        for _ in 0..count {
+            fractions.push((previous.clone(), c.one()));
+
+            let next = c.sub(&c.mul(&current, &step_value), &previous);
+            previous = std::mem::replace(&mut current, next);
+        }
+
+        return fractions;
+    }
+
+    fn infinity(&self) -> (T, T) {
+        // V_0 = 2 stands for α^0 = 1
+        (self.ladder(&0i64).v, self.context.one())
+    }
+
+    fn context(&self) -> &ModContext<T> {
+        &self.context
+    }
+}
+
+/// runs williams' p + 1 method with the bounds B1 and B2 on an odd composite. it finds a prime p
+/// if p + 1 is B1-smooth up to one more prime up to B2, returns None if no seed finds a factor.
+/// a B2 up to B1 skips stage 2
+pub fn williams_pp1<T: Number>(number: &T, b1: u64, b2: u64) -> Result<Option<T>, T> {
+    if *number < T::from(2) {
+        return Err(Error::InvalidInput("number must be at least two"));
+    }
+    if is_prime(number) {
+        return Err(Error::InputIsPrime);
+    }
+    if number.is_even() {
+        return Err(Error::InvalidInput("number must be odd"));
+    }
+    if b1 < 2 {
+        return Err(Error::InvalidInput("B1 must be at least two"));
+    }
+
+    let context = Arc::new(ModContext::new(number));
+    let stage_two = (b2 > b1).then(|| StageTwo::new(b1, b2, StageTwoMethod::Auto));
+
+    // a seed only works for the primes with (P² - 4 / p) = -1, the others see p - 1 instead
+    for (numerator, denominator) in SEEDS {
+        let seed = match context.inv(&context.encode(&T::from(denominator))) {
+            Ok(inverse) => context.mul(&context.encode(&T::from(numerator)), &inverse),
+            Err(Error::NotInvertible { gcd }) => return Ok(non_trivial_divisor(&gcd, number)),
+            Err(error) => return Err(error),
+        };
+
+        let mut point = LucasPoint::new(seed, context.clone());
+        for power in prime_powers(b1) {
+            point = point.ladder(&(power as i64));
+        }
+
+        if let Some(factor) = smooth_order_factor(&point, stage_two.as_ref()) {
+            return Ok(Some(factor));
+        }
+    }
+
+    Ok(None)
+}
+
+/// finishes p - 1 and p + 1 after stage 1. V_k = 2 modulo p if the order divides k, otherwise
+/// stage 2 looks for one more prime
+pub(crate) fn smooth_order_factor<T: Number>(point: &LucasPoint<T>, stage_two: Option<&StageTwo>) -> Option<T> {
+    let c = &point.context;
+    let two = c.add(&c.one(), &c.one());
+
+    let factor = non_trivial_divisor(&c.decode(&c.sub(&point.v, &two)), c.modulus());
+    if factor.is_some() {
+        return factor;
+    }
+
+    non_trivial_divisor(&stage_two?.run(point), c.modulus())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigInt;
+
+    #[test]
+    fn lucas_ladder_matches_the_recurrence() {
+        let n = 1_000_003i64 * 1_000_033;
+        let context = Arc::new(ModContext::new(&n));
+        let point = LucasPoint::new(context.encode(&5), context.clone());
+
+        // V_0 = 2, V_1 = P, V_k+1 = P·V_k - V_k-1
+        let mut values = vec![2i128, 5];
+        for k in 2..100 {
+            values.push((5 * values[k - 1] - values[k - 2]).rem_euclid(n as i128));
+        }
+        for (k, value) in values.iter().enumerate() {
+            assert_eq!(context.decode(&point.ladder(&(k as i64)).v) as i128, *value);
+        }
+
+        let fractions = point.progression(7, 10, 9);
+        for (index, (numerator, _)) in fractions.iter().enumerate() {
+            assert_eq!(context.decode(numerator) as i128, values[7 + 10 * index]);
+        }
+    }
+
+    #[test]
+    fn pp1_finds_primes_with_smooth_p_plus_one() {
+        // p + 1 = 2^3·3·5·19·439 while p - 1 = 2·500_459, q - 1 and q + 1 have large factors
+        let (p, q) = (1_000_919i64, 1_000_171i64);
+        assert_eq!(williams_pp1(&(p * q), 500, 0).unwrap(), Some(p));
+        assert_eq!(williams_pp1(&(p * q), 100, 0).unwrap(), None);
+        assert_eq!(williams_pp1(&(p * q), 100, 500).unwrap(), Some(p));
+
+        let big = BigInt::from(10).pow(30) + 99;
+        assert_eq!(williams_pp1(&(&big * p), 100, 500).unwrap(), Some(BigInt::from(p)));
+        assert!(matches!(williams_pp1(&big, 100, 500), Err(Error::InputIsPrime)));
+        assert!(matches!(williams_pp1(&(p * q), 1, 500), Err(Error::InvalidInput(_))));
+    }
+}
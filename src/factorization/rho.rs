@@ -0,0 +1,123 @@
+use crate::arithmetic::{add_mod, gcd, mod_mul, sub_mod, Number};
+use crate::error::{Error, Result};
+use crate::primality::is_prime;
+
+/// steps between two gcds, the differences are multiplied up in between
+const BATCH: u64 = 128;
+/// cycle length brent's search gives up at, far beyond the expected √p for 20 digits
+const MAX_CYCLE: u64 = 1 << 26;
+/// polynomials x² + c tried before giving up
+const MAX_ATTEMPTS: u32 = 16;
+
+/// runs pollard's rho with brent's cycle detection on the walk x -> x² + c. the differences
+/// of the walk are multiplied up, so only every 128th step takes a gcd. returns a proper divisor,
+/// InputIsPrime for primes and IterationLimitReached if no polynomial finds one
+pub fn pollard_rho<T: Number>(number: &T) -> Result<T, T> {
+    if *number < T::from(2) {
+        return Err(Error::InvalidInput("number must be at least two"));
+    }
+    if is_prime(number) {
+        return Err(Error::InputIsPrime);
+    }
+    if number.is_even() {
+        return Ok(T::from(2));
+    }
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..MAX_ATTEMPTS {
+        let start = T::random_below(&mut rng, number);
+        let constant = T::random_below(&mut rng, &(number.clone() - T::from(3))) + T::one();
+
+        if let Some(factor) = brent(number, &start, &constant) {
+            return Ok(factor);
+        }
+    }
+
+    return Err(Error::IterationLimitReached);
+}
+
+/// searches a cycle of the walk from a start, returns None if it closes modulo all divisors
+/// at once or grows beyond the limit
+fn brent<T: Number>(number: &T, start: &T, constant: &T) -> Option<T> {
+    let step = |x: &T| add_mod(&mod_mul(x, x, number), constant, number);
+    let (mut y, mut product, mut divisor) = (start.clone(), T::one(), T::one());
+    let (mut x, mut saved);
+    let mut length = 1;
+
+    // compare x with the next 'length' values, then move x ahead and double the length
+    loop {
+        x = y.clone();
+        saved = y.clone();
+        for _ in 0..length {
+            y = step(&y);
+        }
+
+        let mut index = 0;
+        while index < length && divisor.is_one() {
+            saved = y.clone();
+            for _ in 0..BATCH.min(length - index) {
+                y = step(&y);
+                product = mod_mul(&product, &sub_mod(&x, &y, number), number);
+            }
+
+            divisor = gcd(&product, number);
+            index += BATCH;
+        }
+
+        if !divisor.is_one() {
+            break;
+        }
+        length *= 2;
+        if length > MAX_CYCLE {
+            return None;
+        }
+    }
+
+    // the batch closed the cycle modulo all divisors, so it is repeated step by step
+    if divisor == *number {
+        loop {
+            saved = step(&saved);
+            divisor = gcd(&sub_mod(&x, &saved, number), number);
+            if !divisor.is_one() {
+                break;
+            }
+        }
+    }
+
+    match divisor == *number {
+        true => None,
+        false => Some(divisor),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigInt;
+
+    #[test]
+    fn rho_splits_composites() {
+        for (p, q) in [(3i64, 5), (101, 103), (65_537, 1_000_003), (999_983, 1_000_003)] {
+            let factor = pollard_rho(&(p * q)).unwrap();
+            assert!(factor == p || factor == q);
+        }
+
+        // twenty digits with a ten digit factor
+        let (p, q) = (9_999_999_967i128, 10_000_000_019i128);
+        let factor = pollard_rho(&(p * q)).unwrap();
+        assert!(factor == p || factor == q);
+
+        let (p, q) = (BigInt::from(4_294_967_291i64), BigInt::from(4_294_967_279i64));
+        let factor = pollard_rho(&(&p * &q)).unwrap();
+        assert!(factor == p || factor == q);
+    }
+
+    #[test]
+    fn rho_rejects_primes_and_handles_squares() {
+        assert!(matches!(pollard_rho(&1_000_003i64), Err(Error::InputIsPrime)));
+        assert!(matches!(pollard_rho(&1i64), Err(Error::InvalidInput(_))));
+        assert_eq!(pollard_rho(&(2 * 1_000_003i64)).unwrap(), 2);
+        assert_eq!(pollard_rho(&(1_000_003i64 * 1_000_003)).unwrap(), 1_000_003);
+    }
+}
@@ -0,0 +1,218 @@
+/// columns up to this weight are merged away, heavier ones would fill the rows too fast
+const MAX_MERGE_WEIGHT: usize = 3;
+
+/// rows kept beyond the columns, each is one more dependency
+const SURPLUS: usize = 64;
+
+/// returns subsets of the rows that sum to zero over GF(2), each row lists the columns of its odd
+/// exponents in increasing order. structured gaussian elimination first shrinks the sparse: it
+/// drops the rows with a column no other row has, which cannot be part of any dependency, merges
+/// the columns of weight two and three away by adding the lightest of their rows to the others,
+/// and drops the heaviest rows beyond the surplus. only the rest is eliminated densely
+pub(super) fn dependencies(rows: &[Vec<usize>], columns: usize) -> Vec<Vec<usize>> {
+    // every row carries the original rows it sums up, None marks a dropped row
+    let mut sparse = rows.iter().enumerate()
+        .map(|(index, row)| Some((row.clone(), vec![index])))
+        .collect::<Vec<_>>();
+    let mut occurrences = vec![vec![]; columns];
+    let mut weights = vec![0usize; columns];
+    for (index, row) in rows.iter().enumerate() {
+        for column in row {
+            occurrences[*column].push(index);
+            weights[*column] += 1;
+        }
+    }
+
+    // every removal and merge may create the next one in the columns of the rows it touched,
+    // so those columns are visited again until none is left
+    let mut pending = (0..columns).rev().collect::<Vec<_>>();
+    let mut queued = vec![true; columns];
+    loop {
+        while let Some(column) = pending.pop() {
+            queued[column] = false;
+            if weights[column] == 0 || weights[column] > MAX_MERGE_WEIGHT {
+                continue;
+            }
+
+            // the lists are cleaned lazily, merges remove columns from rows and add others
+            occurrences[column].retain(|row| sparse[*row].as_ref().is_some_and(|(columns, _)| columns.binary_search(&column).is_ok()));
+            occurrences[column].sort_unstable();
+            occurrences[column].dedup();
+            let live = occurrences[column].clone();
+
+            // a singleton row is dropped, otherwise the lightest row is added to the others
+            let pivot = *live.iter().min_by_key(|row| sparse[**row].as_ref().map_or(0, |(columns, _)| columns.len())).unwrap();
+            let (pivot_columns, pivot_history) = sparse[pivot].take().unwrap();
+            for row in live.into_iter().filter(|row| *row != pivot) {
+                let (row_columns, row_history) = sparse[row].take().unwrap();
+                for other in &pivot_columns {
+                    match row_columns.binary_search(other).is_ok() {
+                        true => weights[*other] -= 1,
+                        false => {
+                            weights[*other] += 1;
+                            occurrences[*other].push(row);
+                        }
+                    }
+                }
+                sparse[row] = Some((symmetric_difference(&row_columns, &pivot_columns), symmetric_difference(&row_history, &pivot_history)));
+            }
+
+            for touched in pivot_columns {
+                weights[touched] -= 1;
+                if !queued[touched] {
+                    queued[touched] = true;
+                    pending.push(touched);
+                }
+            }
+        }
+
+        // more rows than the columns need only add density, the heaviest ones go first
+        let mut kept = (0..sparse.len()).filter(|row| sparse[*row].is_some()).collect::<Vec<_>>();
+        let width = weights.iter().filter(|weight| **weight > 0).count();
+        if kept.len() <= width + SURPLUS {
+            break;
+        }
+
+        kept.sort_by_key(|row| sparse[*row].as_ref().map_or(0, |(columns, _)| columns.len()));
+        for row in kept.drain(width + SURPLUS..) {
+            for column in sparse[row].take().unwrap().0 {
+                weights[column] -= 1;
+                if !queued[column] {
+                    queued[column] = true;
+                    pending.push(column);
+                }
+            }
+        }
+    }
+
+    // the remaining columns are numbered densely
+    let kept = sparse.into_iter().flatten().collect::<Vec<_>>();
+    let mut dense = vec![usize::MAX; columns];
+    let mut width = 0;
+    for column in kept.iter().flat_map(|(columns, _)| columns) {
+        if dense[*column] == usize::MAX {
+            dense[*column] = width;
+            width += 1;
+        }
+    }
+
+    // every row carries the bit vector of its columns and of the remaining rows it sums up
+    let (words, history_words) = (width.div_ceil(64), kept.len().div_ceil(64));
+    let mut matrix = kept.iter().enumerate().map(|(position, (columns, _))| {
+        let mut bits = vec![0u64; words];
+        for column in columns.iter().map(|column| dense[*column]) {
+            bits[column / 64] ^= 1 << (column % 64);
+        }
+        let mut history = vec![0u64; history_words];
+        history[position / 64] |= 1 << (position % 64);
+        (bits, history)
+    }).collect::<Vec<_>>();
+
+    let mut pivot = vec![false; matrix.len()];
+    for column in 0..width {
+        let (word, bit) = (column / 64, 1u64 << (column % 64));
+        let Some(chosen) = (0..matrix.len()).find(|row| !pivot[*row] && matrix[*row].0[word] & bit != 0) else {
+            continue;
+        };
+        pivot[chosen] = true;
+
+        let (bits, history) = matrix[chosen].clone();
+        for (row, (other_bits, other_history)) in matrix.iter_mut().enumerate() {
+            if row != chosen && other_bits[word] & bit != 0 {
+                other_bits.iter_mut().zip(&bits).for_each(|(a, b)| *a ^= b);
+                other_history.iter_mut().zip(&history).for_each(|(a, b)| *a ^= b);
+            }
+        }
+    }
+
+    // rows that never became a pivot are zero now, their history names the remaining rows of a
+    // dependency, which in turn sum up original rows
+    matrix.into_iter().zip(pivot)
+        .filter(|(_, pivot)| !pivot)
+        .map(|((_, history), _)| {
+            let mut odd = vec![false; rows.len()];
+            (0..kept.len()).filter(|position| history[position / 64] >> (position % 64) & 1 == 1)
+                .flat_map(|position| &kept[position].1)
+                .for_each(|row| odd[*row] ^= true);
+            (0..rows.len()).filter(|row| odd[*row]).collect::<Vec<_>>()
+        })
+        .filter(|dependency| !dependency.is_empty())
+        .collect()
+}
+
+/// returns the sorted elements that occur in exactly one of two sorted lists, the sum over GF(2)
+fn symmetric_difference(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let mut sum = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => { sum.push(a[i]); i += 1; }
+            std::cmp::Ordering::Greater => { sum.push(b[j]); j += 1; }
+            std::cmp::Ordering::Equal => { i += 1; j += 1; }
+        }
+    }
+    sum.extend_from_slice(&a[i..]);
+    sum.extend_from_slice(&b[j..]);
+
+    return sum;
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn dependencies_sum_to_zero() {
+        let mut rng = rand::thread_rng();
+        let columns = 150;
+        let rows = (0..170)
+            .map(|_| {
+                let mut row = (0..rng.gen_range(1..8)).map(|_| rng.gen_range(0..columns)).collect::<Vec<_>>();
+                row.sort_unstable();
+                row.dedup();
+                row
+            })
+            .collect::<Vec<_>>();
+
+        let found = dependencies(&rows, columns);
+        assert!(found.len() >= 20);
+        for dependency in found {
+            assert!(!dependency.is_empty());
+            let mut parity = vec![false; columns];
+            for column in dependency.iter().flat_map(|row| &rows[*row]) {
+                parity[*column] ^= true;
+            }
+            assert!(parity.iter().all(|odd| !odd));
+        }
+    }
+
+    #[test]
+    fn sparse_columns_are_merged_before_the_dense_elimination() {
+        // like relations, every row has a few small primes and two of many large ones, so a
+        // dense elimination of the whole matrix would take billions of word operations
+        let mut rng = rand::thread_rng();
+        let (columns, heavy) = (20_000, 40);
+        let rows = (0..columns + 50)
+            .map(|_| {
+                let mut row = (0..6).map(|_| rng.gen_range(0..heavy)).collect::<Vec<_>>();
+                row.extend((0..2).map(|_| rng.gen_range(heavy..columns)));
+                row.sort_unstable();
+                row.dedup();
+                row
+            })
+            .collect::<Vec<_>>();
+
+        let found = dependencies(&rows, columns);
+        assert!(!found.is_empty());
+        for dependency in found {
+            let mut parity = vec![false; columns];
+            for column in dependency.iter().flat_map(|row| &rows[*row]) {
+                parity[*column] ^= true;
+            }
+            assert!(parity.iter().all(|odd| !odd));
+        }
+    }
+}
@@ -0,0 +1,333 @@
+//! self-initializing quadratic sieve for composites of about 30 to 100 digits
+
+mod matrix;
+mod sieve;
+
+use std::collections::HashMap;
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive, Zero};
+
+use crate::arithmetic::{is_perfect_power, sqrt_mod, Number};
+use crate::error::{Error, Result};
+use crate::factorization::small::small_factor;
+use crate::primality::{is_prime, jacobi_symbol};
+use crate::sieve::Primes;
+
+use self::matrix::dependencies;
+use self::sieve::Sieve;
+
+/// (bits of kn, factor base size, large prime bound as multiple of the largest prime, sieve
+/// interval length) after msieve, the factor base size is interpolated between the rows
+const PARAMETERS: [(u64, usize, u64, u32); 11] = [
+    (64, 100, 40, 65_536),
+    (128, 450, 40, 65_536),
+    (183, 2_000, 40, 65_536),
+    (200, 3_000, 50, 65_536),
+    (212, 5_400, 50, 196_608),
+    (233, 10_000, 100, 196_608),
+    (249, 27_000, 100, 196_608),
+    (266, 50_000, 100, 196_608),
+    (283, 55_000, 80, 196_608),
+    (298, 60_000, 80, 589_824),
+    (332, 100_000, 150, 589_824),
+];
+
+/// squarefree multipliers the knuth-schroeppel function chooses from
+const MULTIPLIERS: [u64; 24] = [1, 3, 5, 7, 11, 13, 15, 17, 19, 21, 23, 29, 31, 33, 35, 37, 39, 41, 43, 47, 51, 53, 55, 57];
+
+/// relations collected beyond the factor base size, every one adds a dependency
+const EXTRA_RELATIONS: usize = 48;
+
+/// polynomials sieved per factor base prime before the sieve gives up. the sizes up to 65 digits
+/// need up to five, so only a number the parameters do not suit reaches the limit
+const POLYNOMIALS_PER_PRIME: usize = 100;
+
+/// relation (Ax + B)² = A·g(x) mod n, which becomes y² = ∏ factors · square² mod n. y is the
+/// square root side, factors are factor base indices with repetition and square collects the
+/// large primes of two combined partial relations
+#[derive(Clone, Debug)]
+pub(crate) struct Relation {
+    y: BigInt,
+    factors: Vec<usize>,
+    square: BigInt,
+}
+
+/// primes p with (kn/p) = 1 and the primes of k, index 0 stands for the sign and index 1 for
+/// two. the roots of kn modulo p and rounded logarithms are kept for the sieve
+pub(crate) struct FactorBase {
+    primes: Vec<u32>,
+    roots: Vec<u32>,
+    logarithms: Vec<u8>,
+}
+
+impl FactorBase {
+    /// collects the first size primes the multiple of the number is a square modulo. fails with
+    /// a prime that divides the number itself
+    fn new(number: &BigInt, multiple: &BigInt, size: usize) -> std::result::Result<Self, u64> {
+        let mut base = FactorBase { primes: vec![1, 2], roots: vec![0, 1], logarithms: vec![0, 1] };
+
+        for prime in Primes::new().skip(1) {
+            if base.primes.len() >= size {
+                break;
+            }
+
+            let residue = (multiple % prime).to_i64().unwrap();
+            if (number % prime).is_zero() {
+                return Err(prime);
+            }
+
+            // the primes of the multiplier divide kn, so their root is zero
+            let root = match residue {
+                0 => Some(0),
+                _ => (jacobi_symbol(&residue, &(prime as i64)) == 1).then(|| sqrt_mod(&residue, &(prime as i64)).unwrap()),
+            };
+            if let Some(root) = root {
+                base.primes.push(prime as u32);
+                base.roots.push(root as u32);
+                base.logarithms.push((prime as f64).log2().round() as u8);
+            }
+        }
+
+        Ok(base)
+    }
+
+    /// returns the largest prime of the factor base
+    fn largest(&self) -> u64 {
+        *self.primes.last().unwrap() as u64
+    }
+}
+
+/// factors a composite with the self-initializing quadratic sieve. the sieve collects relations
+/// (Ax + B)² = A·g(x) mod n with g(x) smooth over the factor base, the linear algebra over GF(2)
+/// combines them to x² = y² mod n and gcd(x - y, n) splits n. word-sized composites are passed
+/// to the methods for them, IterationLimitReached means the sieve ran out of polynomials before
+/// it collected enough relations or no dependency split the number
+pub fn siqs<T: Number>(number: &T) -> Result<T, T> {
+    if *number < T::from(2) {
+        return Err(Error::InvalidInput("number must be at least two"));
+    }
+    if is_prime(number) {
+        return Err(Error::InputIsPrime);
+    }
+    if number.is_even() {
+        return Err(Error::InvalidInput("number must be odd"));
+    }
+    if number.msb_position() < 64 {
+        let word = number.to_limbs().first().copied().unwrap_or(0);
+        return small_factor(word).map(|factor| T::from_limbs(&[factor])).ok_or(Error::IterationLimitReached);
+    }
+
+    // every dependency of a prime power is trivial
+    if let Some((base, _)) = is_perfect_power(number) {
+        return Ok(base);
+    }
+
+    match quadratic_sieve(&BigInt::from_limbs(&number.to_limbs()), POLYNOMIALS_PER_PRIME) {
+        Some(factor) => Ok(T::from_limbs(&factor.to_limbs())),
+        None => Err(Error::IterationLimitReached),
+    }
+}
+
+/// runs the sieve on an odd composite that is no square, returns a proper divisor. None if the
+/// sieve needs more than the given polynomials per factor base prime
+fn quadratic_sieve(number: &BigInt, polynomials_per_prime: usize) -> Option<BigInt> {
+    let multiple = number * multiplier(number);
+    let (size, large_prime_factor, interval) = parameters(multiple.bits());
+
+    let base = match FactorBase::new(number, &multiple, size) {
+        Ok(base) => base,
+        Err(prime) => return Some(BigInt::from(prime)),
+    };
+
+    let mut sieve = Sieve::new(&base, &multiple, interval / 2, base.largest() * large_prime_factor);
+    let mut relations = vec![];
+    let mut partials: HashMap<u64, Relation> = HashMap::new();
+
+    // the matrix has a column for every factor base index, more rows than columns guarantee
+    // dependencies, each splits n with probability one half at least
+    let mut polynomials = 0;
+    while relations.len() < base.primes.len() + EXTRA_RELATIONS {
+        if polynomials >= base.primes.len() * polynomials_per_prime {
+            return None;
+        }
+        polynomials += 1;
+
+        for (relation, large_prime) in sieve.next_polynomial(number) {
+            if large_prime == 1 {
+                relations.push(relation);
+                continue;
+            }
+
+            // two partial relations with the same large prime multiply to a full one
+            match partials.remove(&large_prime) {
+                Some(partner) => relations.push(Relation {
+                    y: relation.y * partner.y % number,
+                    factors: [relation.factors, partner.factors].concat(),
+                    square: relation.square * partner.square * large_prime,
+                }),
+                None => {
+                    partials.insert(large_prime, relation);
+                }
+            }
+        }
+    }
+
+    let rows = relations.iter().map(|relation| odd_exponents(&relation.factors)).collect::<Vec<_>>();
+    dependencies(&rows, base.primes.len()).into_iter()
+        .filter_map(|dependency| split(&dependency, &relations, &base, number))
+        .next()
+}
+
+/// combines the relations of a dependency to x² = y² mod n, returns gcd(x - y, n) if it splits n
+fn split(dependency: &[usize], relations: &[Relation], base: &FactorBase, number: &BigInt) -> Option<BigInt> {
+    let mut x = BigInt::one();
+    let mut y = BigInt::one();
+    let mut exponents = vec![0u32; base.primes.len()];
+
+    for relation in dependency.iter().map(|index| &relations[*index]) {
+        x = x * &relation.y % number;
+        y = y * &relation.square % number;
+        for factor in &relation.factors {
+            exponents[*factor] += 1;
+        }
+    }
+
+    // the product of the relations is a square, so its root takes half of every exponent
+    for (prime, exponent) in base.primes.iter().zip(exponents).skip(1) {
+        y = y * BigInt::from(*prime).modpow(&BigInt::from(exponent / 2), number) % number;
+    }
+
+    let factor = (x - y).gcd(number);
+    match factor > BigInt::one() && factor < *number {
+        true => Some(factor),
+        false => None,
+    }
+}
+
+/// returns the factor base indices that occur an odd number of times
+fn odd_exponents(factors: &[usize]) -> Vec<usize> {
+    let mut sorted = factors.to_vec();
+    sorted.sort_unstable();
+
+    let mut odd: Vec<usize> = vec![];
+    for factor in sorted {
+        match odd.last() == Some(&factor) {
+            true => {
+                odd.pop();
+            }
+            false => odd.push(factor),
+        }
+    }
+
+    return odd;
+}
+
+/// chooses the multiplier k with the knuth-schroeppel function, which rewards small primes that
+/// kn is a square modulo and penalizes the growth of kn
+fn multiplier(number: &BigInt) -> u64 {
+    let score = |k: u64| {
+        let multiple = number * k;
+        let mut score = -0.5 * (k as f64).ln();
+
+        // two divides g(x) more often the closer kn is to 1 mod 8
+        score += match (&multiple % 8u32).to_u32().unwrap() {
+            1 => 2.0 * 2f64.ln(),
+            5 => 2f64.ln(),
+            3 | 7 => 0.5 * 2f64.ln(),
+            _ => 0.0,
+        };
+
+        for prime in Primes::up_to(1_000).skip(1) {
+            let residue = (&multiple % prime).to_i64().unwrap();
+            let contribution = (prime as f64).ln();
+            score += match (k.is_multiple_of(prime), jacobi_symbol(&residue, &(prime as i64))) {
+                (true, _) => contribution / prime as f64,
+                (false, 1) => 2.0 * contribution / (prime - 1) as f64,
+                _ => 0.0,
+            };
+        }
+        score
+    };
+
+    MULTIPLIERS.into_iter()
+        .max_by(|a, b| score(*a).total_cmp(&score(*b)))
+        .unwrap_or(1)
+}
+
+/// returns the factor base size, the large prime multiple and the interval length for kn of
+/// the given size, the factor base size is interpolated linearly between the rows
+fn parameters(bits: u64) -> (usize, u64, u32) {
+    let (first, last) = (PARAMETERS[0], PARAMETERS[PARAMETERS.len() - 1]);
+    if bits <= first.0 {
+        return (first.1, first.2, first.3);
+    }
+
+    for pair in PARAMETERS.windows(2) {
+        let ((low, low_size, _, _), (high, high_size, factor, interval)) = (pair[0], pair[1]);
+        if bits <= high {
+            let size = low_size + (high_size - low_size) * (bits - low) as usize / (high - low) as usize;
+            return (size, factor, interval);
+        }
+    }
+
+    return (last.1, last.2, last.3);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiplier_and_parameters() {
+        // 8·k + 1 rewards two, every candidate k is squarefree
+        let number = BigInt::from(1_000_003i64) * BigInt::from(1_000_033i64);
+        let k = multiplier(&number);
+        assert!(MULTIPLIERS.contains(&k));
+        assert_eq!(parameters(50), (100, 40, 65_536));
+        assert_eq!(parameters(128), (450, 40, 65_536));
+        assert_eq!(parameters(1_000), (100_000, 150, 589_824));
+        assert_eq!(odd_exponents(&[3, 1, 3, 3, 2, 1]), vec![2, 3]);
+
+        // the odd primes of the multiplier stay in the factor base with the root zero
+        let number = BigInt::from(100_000_000_000_031i64) * BigInt::from(100_000_000_000_097i64);
+        let k = multiplier(&number);
+        assert_eq!(k, 31);
+        let base = FactorBase::new(&number, &(&number * k), 100).unwrap();
+        for prime in Primes::up_to(k).skip(1).filter(|prime| k.is_multiple_of(*prime)) {
+            let index = base.primes.iter().position(|p| *p as u64 == prime).unwrap();
+            assert_eq!(base.roots[index], 0);
+        }
+    }
+
+    #[test]
+    fn siqs_splits_semiprimes() {
+        let (p, q) = (BigInt::from(1_000_000_007i64), BigInt::from(998_244_353i64));
+        let factor = siqs(&(&p * &q)).unwrap();
+        assert!(factor == p || factor == q);
+
+        // 30 digits with two balanced 15 digit primes
+        let (p, q) = (BigInt::from(100_000_000_000_031i64), BigInt::from(100_000_000_000_067i64));
+        let factor = siqs(&(&p * &q)).unwrap();
+        assert!(factor == p || factor == q);
+
+        // the multiplier 31 is sieved with the single root of g modulo 31
+        let q = BigInt::from(100_000_000_000_097i64);
+        let factor = siqs(&(&p * &q)).unwrap();
+        assert!(factor == p || factor == q);
+
+        let (p, q) = (1_000_000_000_039i128, 1_000_000_000_061i128);
+        let factor = siqs(&(p * q)).unwrap();
+        assert!(factor == p || factor == q);
+
+        // the sieve gives up once it runs out of polynomials
+        let number = BigInt::from(1_000_000_000_039i64) * BigInt::from(1_000_000_000_061i64);
+        assert_eq!(quadratic_sieve(&number, 0), None);
+
+        assert!(matches!(siqs(&BigInt::from(1_000_003)), Err(Error::InputIsPrime)));
+        assert_eq!(siqs(&(p * p)).unwrap(), p);
+        assert_eq!(siqs(&BigInt::from(1_000_003).pow(5)).unwrap(), BigInt::from(1_000_003));
+    }
+}
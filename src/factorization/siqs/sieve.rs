@@ -0,0 +1,286 @@
+use std::collections::HashSet;
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use rand::seq::SliceRandom;
+
+use super::{FactorBase, Relation};
+use crate::arithmetic::mod_inv;
+
+/// primes below this bound are left out of the sieve, they cost the most time and add the least
+const SMALL_PRIME_BOUND: u32 = 30;
+
+/// bits the skipped small primes, the rounded logarithms and the smaller values of g(x) inside
+/// the interval may miss, subtracted from the threshold
+const SMALL_PRIME_SLACK: u64 = 12;
+
+/// average size of the primes A is built from, small enough to find many A of the right size
+const A_PRIME_BITS: u64 = 11;
+
+/// tries to find a new A before a repeated one is accepted
+const A_ATTEMPTS: usize = 100;
+
+/// sieve over the polynomials g(x) = ((Ax + B)² - kn)/A for x in -M..M. every A is the product
+/// of s factor base primes with A ≈ √(2kn)/M, which keeps g(x) below M·√(kn/2). the 2^(s-1)
+/// polynomials of one A take their B from the gray code, so the roots of the next one follow
+/// from the current ones by a single addition per prime
+pub(super) struct Sieve<'a> {
+    base: &'a FactorBase,
+    multiple: BigInt,
+    half_width: u32,
+    large_prime_bound: u64,
+    threshold: u8,
+    first_sieved: usize,
+    used: HashSet<Vec<usize>>,
+    interval: Vec<u8>,
+    /// the current polynomial with the primes of A and the terms B_l with B = Σ ±B_l
+    a: BigInt,
+    b: BigInt,
+    c: BigInt,
+    a_factors: Vec<usize>,
+    in_a: Vec<bool>,
+    b_terms: Vec<BigInt>,
+    /// roots x of g modulo every factor base prime and the steps 2·B_l/A mod p between B's
+    roots: Vec<(u32, u32)>,
+    steps: Vec<Vec<u32>>,
+    /// index of the polynomial within the gray code of the current A
+    index: usize,
+}
+
+impl<'a> Sieve<'a> {
+    /// prepares the sieve of kn over an interval of length 2M, partial relations keep one
+    /// prime up to the large prime bound
+    pub(super) fn new(base: &'a FactorBase, multiple: &BigInt, half_width: u32, large_prime_bound: u64) -> Self {
+        // log2 |g(x)| is at most log2(M) + log2(kn)/2, the large prime may stay undivided
+        let maximum = (half_width as f64).log2() as u64 + multiple.bits() / 2;
+        let threshold = maximum.saturating_sub((large_prime_bound as f64).log2() as u64 + SMALL_PRIME_SLACK);
+
+        Sieve {
+            base,
+            multiple: multiple.clone(),
+            half_width,
+            large_prime_bound,
+            threshold: threshold.min(u8::MAX as u64) as u8,
+            first_sieved: base.primes.iter().position(|prime| *prime >= SMALL_PRIME_BOUND).unwrap_or(base.primes.len()),
+            used: HashSet::new(),
+            interval: vec![0; 2 * half_width as usize],
+            a: BigInt::zero(),
+            b: BigInt::zero(),
+            c: BigInt::zero(),
+            a_factors: vec![],
+            in_a: vec![false; base.primes.len()],
+            b_terms: vec![],
+            roots: vec![(0, 0); base.primes.len()],
+            steps: vec![],
+            index: 0,
+        }
+    }
+
+    /// sieves the next polynomial, returns its relations with their large prime, one for full
+    /// relations. a new A is chosen once all B of the current one are used
+    pub(super) fn next_polynomial(&mut self, number: &BigInt) -> Vec<(Relation, u64)> {
+        match self.index == 0 || self.index >= 1 << (self.a_factors.len() - 1) {
+            true => self.next_a(),
+            false => self.next_b(),
+        }
+        self.index += 1;
+
+        self.sieve();
+        let candidates = self.interval.iter().enumerate()
+            .filter(|(_, logarithm)| **logarithm >= self.threshold)
+            .map(|(position, _)| position as i64 - self.half_width as i64)
+            .collect::<Vec<_>>();
+
+        candidates.into_iter().filter_map(|x| self.relation(x, number)).collect()
+    }
+
+    /// chooses a new A from s primes around 2^11 whose product is close to √(2kn)/M and sets up
+    /// the first B and the roots of its polynomial
+    fn next_a(&mut self) {
+        let target = (&self.multiple * 2u32).sqrt() / self.half_width;
+        let primes = &self.base.primes;
+
+        // s primes of the average size target^(1/s), which the factor base has to reach
+        let mut count = ((target.bits() + A_PRIME_BITS / 2) / A_PRIME_BITS).max(1);
+        while count < 20 && target.bits() / count + 1 > (self.base.largest() as f64).log2() as u64 {
+            count += 1;
+        }
+        let average = 2f64.powf(target.bits() as f64 / count as f64);
+
+        // a prime of the multiplier divides kn only once, so B² = kn mod A fails for it
+        let candidates = (self.first_sieved.max(2)..primes.len())
+            .filter(|index| self.base.roots[*index] != 0)
+            .collect::<Vec<_>>();
+        let mut pool = candidates.iter().copied()
+            .filter(|index| (average / 2.0..average * 2.0).contains(&(primes[*index] as f64)))
+            .collect::<Vec<_>>();
+        if pool.len() < count as usize + 2 {
+            pool = candidates.clone();
+        }
+
+        let mut rng = rand::thread_rng();
+        for attempt in 0..A_ATTEMPTS {
+            let mut factors = pool.choose_multiple(&mut rng, count as usize - 1).copied().collect::<Vec<_>>();
+            let product = factors.iter().fold(BigInt::one(), |product, index| product * primes[*index]);
+
+            // the last prime brings the product as close to the target as possible
+            let wanted = (&target / &product).to_u64().unwrap_or(u64::MAX);
+            let last = candidates.iter().copied()
+                .filter(|index| !factors.contains(index))
+                .min_by_key(|index| (primes[*index] as u64).abs_diff(wanted));
+            factors.extend(last);
+            factors.sort_unstable();
+
+            if self.used.insert(factors.clone()) || attempt == A_ATTEMPTS - 1 {
+                self.set_a(factors);
+                return;
+            }
+        }
+    }
+
+    /// computes B and the roots of the first polynomial of an A. B_l = (A/q_l)·γ_l with
+    /// γ_l = √kn·(A/q_l)^(-1) mod q_l makes B² = kn mod A, so g(x) has integer coefficients
+    fn set_a(&mut self, factors: Vec<usize>) {
+        let base = self.base;
+        self.a = factors.iter().fold(BigInt::one(), |product, index| product * base.primes[*index]);
+        self.in_a = vec![false; base.primes.len()];
+        for index in &factors {
+            self.in_a[*index] = true;
+        }
+
+        self.b_terms = factors.iter().map(|index| {
+            let prime = base.primes[*index] as i64;
+            let cofactor = &self.a / prime;
+            let inverse = mod_inv(&(&cofactor % prime).to_i64().unwrap(), &prime).unwrap();
+            let gamma = (base.roots[*index] as i64 * inverse).rem_euclid(prime);
+            cofactor * gamma.min(prime - gamma)
+        }).collect();
+        self.b = self.b_terms.iter().sum();
+        self.a_factors = factors;
+        self.c = (&self.b * &self.b - &self.multiple) / &self.a;
+
+        self.steps = vec![vec![0; base.primes.len()]; self.b_terms.len()];
+        for index in self.first_sieved.max(2)..base.primes.len() {
+            if self.in_a[index] {
+                continue;
+            }
+
+            let prime = base.primes[index] as i64;
+            let inverse = mod_inv(&(&self.a % prime).to_i64().unwrap(), &prime).unwrap();
+            let (root, b) = (base.roots[index] as i64, (&self.b % prime).to_i64().unwrap());
+            self.roots[index] = (
+                (inverse * (root - b)).rem_euclid(prime) as u32,
+                (inverse * (-root - b)).rem_euclid(prime) as u32,
+            );
+
+            for (term, steps) in self.b_terms.iter().zip(self.steps.iter_mut()) {
+                steps[index] = (2 * (term % prime).to_i64().unwrap() * inverse % prime) as u32;
+            }
+        }
+        self.index = 0;
+    }
+
+    /// moves to the next B of the gray code, which flips the sign of a single term B_l. the
+    /// roots A^(-1)·(±√kn - B) move by 2·B_l·A^(-1) in the opposite direction
+    fn next_b(&mut self) {
+        let term = self.index.trailing_zeros() as usize;
+        let negate = (self.index ^ (self.index >> 1)) >> term & 1 == 1;
+
+        let twice = &self.b_terms[term] * 2;
+        self.b = match negate {
+            true => &self.b - twice,
+            false => &self.b + twice,
+        };
+        self.c = (&self.b * &self.b - &self.multiple) / &self.a;
+
+        for index in self.first_sieved.max(2)..self.base.primes.len() {
+            if self.in_a[index] {
+                continue;
+            }
+
+            let prime = self.base.primes[index];
+            let step = match negate {
+                true => self.steps[term][index],
+                false => prime - self.steps[term][index],
+            };
+            let (first, second) = self.roots[index];
+            self.roots[index] = ((first + step) % prime, (second + step) % prime);
+        }
+    }
+
+    /// adds the logarithm of every sieved prime at the positions g(x) is divisible by it
+    fn sieve(&mut self) {
+        self.interval.fill(0);
+        let length = self.interval.len();
+
+        for index in self.first_sieved.max(2)..self.base.primes.len() {
+            if self.in_a[index] {
+                continue;
+            }
+
+            let (prime, logarithm) = (self.base.primes[index], self.base.logarithms[index]);
+            let offset = self.half_width % prime;
+            let (first, second) = self.roots[index];
+
+            let roots = match first == second {
+                true => &[first][..],
+                false => &[first, second][..],
+            };
+            for root in roots {
+                let mut position = ((root + offset) % prime) as usize;
+                while position < length {
+                    self.interval[position] = self.interval[position].saturating_add(logarithm);
+                    position += prime as usize;
+                }
+            }
+        }
+    }
+
+    /// divides g(x) by the factor base, returns the relation and its large prime if at most one
+    /// prime above the factor base is left. only primes whose root matches x are divided
+    fn relation(&self, x: i64, number: &BigInt) -> Option<(Relation, u64)> {
+        let position = BigInt::from(x);
+        let mut value: BigInt = (&self.a * &position + &self.b * 2) * &position + &self.c;
+        if value.is_zero() {
+            return None;
+        }
+
+        let mut factors = self.a_factors.clone();
+        if value.is_negative() {
+            factors.push(0);
+            value = -value;
+        }
+        while value.is_even() {
+            value >>= 1;
+            factors.push(1);
+        }
+
+        for index in 2..self.base.primes.len() {
+            let prime = self.base.primes[index];
+            let residue = x.rem_euclid(prime as i64) as u32;
+            let (first, second) = self.roots[index];
+
+            // the small primes and the primes of A have no roots the sieve kept up to date
+            let candidate = index < self.first_sieved || self.in_a[index] || residue == first || residue == second;
+            if !candidate {
+                continue;
+            }
+            loop {
+                let (quotient, remainder) = value.div_rem(&BigInt::from(prime));
+                if !remainder.is_zero() {
+                    break;
+                }
+                value = quotient;
+                factors.push(index);
+            }
+        }
+
+        let large_prime = match value.to_u64() {
+            Some(large_prime) if large_prime < self.large_prime_bound => large_prime,
+            _ => return None,
+        };
+        let y = (&self.a * &position + &self.b).mod_floor(number);
+        Some((Relation { y, factors, square: BigInt::one() }, large_prime))
+    }
+}
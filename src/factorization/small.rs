@@ -0,0 +1,250 @@
+//! factoring methods for word-sized numbers, which work on machine integers instead of curves
+
+use num_integer::{gcd, Roots};
+
+/// bit i is set if i is a square modulo 64, which rules out 52 of 64 residues before any root
+const SQUARES_MOD_64: u64 = 0x0202_0212_0203_0213;
+
+/// squarefree multipliers of SQUFOF, each k·n gives the cycle another chance
+const MULTIPLIERS: [u128; 16] = [1, 3, 5, 7, 11, 15, 21, 33, 35, 55, 77, 105, 165, 231, 385, 1_155];
+
+/// hart's method is tried below this size, lehman's bound n^(1/3) is small enough there as well
+const HART_BITS: u32 = 42;
+
+/// returns a proper divisor of a composite below 2^64, None for one and primes. hart's one
+/// line factorization runs first for small numbers, SQUFOF for larger ones and lehman's method,
+/// which always succeeds, if the first one fails
+pub fn small_factor(number: u64) -> Option<u64> {
+    if number < 4 {
+        return None;
+    }
+    if number.is_multiple_of(2) {
+        return Some(2);
+    }
+    if let Some(root) = square_root(number as u128) {
+        return Some(root as u64);
+    }
+
+    let found = match number < 1 << HART_BITS {
+        true => hart_olf(number),
+        false => squfof(number as u128).map(|factor| factor as u64),
+    };
+
+    found.or_else(|| lehman(number))
+}
+
+/// runs lehman's method, which factors every n in O(n^(1/3)): trial division up to n^(1/3), then
+/// for every k up to n^(1/3) a search for a² - 4kn = b² with a close to √(4kn). returns a proper
+/// divisor, None for one and primes
+pub fn lehman(number: u64) -> Option<u64> {
+    if number < 4 {
+        return None;
+    }
+
+    let cube_root = number.cbrt();
+    for divisor in 2..=cube_root.max(2) {
+        if number.is_multiple_of(divisor) {
+            return Some(divisor);
+        }
+    }
+
+    // without divisors up to n^(1/3), n = p·q with p/q close to a fraction with denominators
+    // up to n^(1/3), so a lies in √(4kn)..√(4kn) + n^(1/6)/(4√k)
+    let sixth_root = (number as f64).powf(1.0 / 6.0);
+    for k in 1..=cube_root as u128 {
+        let four_kn = 4 * k * number as u128;
+        let low = ceil_sqrt(four_kn);
+        let high = four_kn.sqrt() + (sixth_root / (4.0 * (k as f64).sqrt())) as u128 + 1;
+
+        for a in low..=high {
+            if let Some(b) = square_root(a * a - four_kn) {
+                let factor = gcd(a + b, number as u128) as u64;
+                if factor > 1 && factor < number {
+                    return Some(factor);
+                }
+            }
+        }
+    }
+
+    return None;
+}
+
+/// runs hart's one line factorization: s = ⌈√(i·n)⌉ for i = 1, 2, ... until s² mod n is a
+/// square t², then gcd(s - t, n) is usually a factor. it is fastest for factors of similar size
+/// and gives up after n^(1/3) tries, returns None then
+pub fn hart_olf(number: u64) -> Option<u64> {
+    if number < 4 {
+        return None;
+    }
+
+    let n = number as u128;
+    for i in 1..=n.cbrt().max(16) {
+        let s = ceil_sqrt(i * n);
+        let residue = s * s % n;
+
+        if let Some(t) = square_root(residue) {
+            let factor = gcd(s - t, n) as u64;
+            if factor > 1 && factor < number {
+                return Some(factor);
+            }
+        }
+    }
+
+    return None;
+}
+
+/// runs shanks' square forms factorization on k·n for every multiplier k with k·n below 2^126.
+/// the continued fraction of √(kn) reaches a square form after about (kn)^(1/4) steps, its
+/// reduced square root then leads to a symmetric form that reveals a factor. returns a proper
+/// divisor or None if every multiplier fails
+pub fn squfof(number: u128) -> Option<u128> {
+    if number < 4 {
+        return None;
+    }
+    if number.is_multiple_of(2) {
+        return Some(2);
+    }
+    if let Some(root) = square_root(number) {
+        return Some(root);
+    }
+
+    for multiplier in MULTIPLIERS {
+        let product = match number.checked_mul(multiplier) {
+            Some(product) if product < 1 << 126 => product as i128,
+            _ => break,
+        };
+
+        if let Some(factor) = squfof_cycle(product, number) {
+            return Some(factor);
+        }
+    }
+
+    return None;
+}
+
+/// runs one SQUFOF cycle on the multiple kn of the number, returns a proper divisor
+fn squfof_cycle(product: i128, number: u128) -> Option<u128> {
+    // the square form shows up after about 2·√(2·√(kn)) steps, three times that is the limit
+    let root = product.sqrt();
+    let limit = 6 * (2 * root).sqrt();
+
+    // forward cycle until a square Q at an even index
+    let (mut p, mut q_previous, mut q) = (root, 1, product - root * root);
+    if q == 0 {
+        return None;
+    }
+
+    let mut square = None;
+    for index in 2..limit {
+        let b = (root + p) / q;
+        let next_p = b * q - p;
+        let next_q = q_previous + b * (p - next_p);
+        (p, q_previous, q) = (next_p, q, next_q);
+
+        if index % 2 == 0 {
+            if let Some(r) = square_root(q as u128) {
+                square = Some(r as i128);
+                break;
+            }
+        }
+    }
+    let r = square?;
+
+    // reverse cycle from the square root of the form until P repeats, Q then shares the factor
+    let b = (root - p) / r;
+    let mut p = b * r + p;
+    let (mut q_previous, mut q) = (r, (product - p * p) / r);
+    for _ in 0..limit {
+        let b = (root + p) / q;
+        let next_p = b * q - p;
+        if next_p == p {
+            break;
+        }
+
+        let next_q = q_previous + b * (p - next_p);
+        (p, q_previous, q) = (next_p, q, next_q);
+    }
+
+    let factor = gcd(number, q.unsigned_abs());
+    match factor > 1 && factor < number {
+        true => Some(factor),
+        false => None,
+    }
+}
+
+/// returns √x if x is a perfect square
+fn square_root(x: u128) -> Option<u128> {
+    if (SQUARES_MOD_64 >> (x % 64)) & 1 == 0 {
+        return None;
+    }
+
+    let root = x.sqrt();
+    match root * root == x {
+        true => Some(root),
+        false => None,
+    }
+}
+
+/// returns ⌈√x⌉
+fn ceil_sqrt(x: u128) -> u128 {
+    let root = x.sqrt();
+    match root * root == x {
+        true => root,
+        false => root + 1,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primality::is_prime;
+
+    /// checks that a found factor is a proper divisor
+    fn divides(factor: Option<u64>, number: u64) -> bool {
+        factor.is_some_and(|factor| factor > 1 && factor < number && number.is_multiple_of(factor))
+    }
+
+    #[test]
+    fn every_method_splits_small_composites() {
+        for number in 4..20_000u64 {
+            let prime = is_prime(&(number as i64));
+
+            assert_eq!(divides(lehman(number), number), !prime, "{}", number);
+            assert_eq!(divides(small_factor(number), number), !prime, "{}", number);
+            if prime {
+                assert_eq!(hart_olf(number), None);
+                assert_eq!(squfof(number as u128), None);
+            }
+            if let Some(factor) = hart_olf(number) {
+                assert!(divides(Some(factor), number));
+            }
+            if let Some(factor) = squfof(number as u128) {
+                assert!(divides(Some(factor as u64), number));
+            }
+        }
+    }
+
+    #[test]
+    fn word_sized_semiprimes() {
+        let semiprimes = [
+            (1_000_003u64, 1_000_033u64),
+            (65_537, 4_294_967_291),
+            (2_147_483_647, 2_147_483_659),
+            (4_294_967_291, 4_294_967_279),
+            (101, 182_636_427_355_429_281),
+        ];
+
+        for (p, q) in semiprimes {
+            let number = p * q;
+            assert!(divides(small_factor(number), number), "{}", number);
+            if number < 1 << 62 {
+                let factor = squfof(number as u128).map(|factor| factor as u64);
+                assert!(divides(factor, number), "{}", number);
+            }
+        }
+        assert!(divides(hart_olf(1_000_003 * 1_000_033), 1_000_003 * 1_000_033));
+        assert!(divides(lehman(1_000_003 * 1_000_033), 1_000_003 * 1_000_033));
+        assert_eq!(small_factor(18_446_744_073_709_551_557), None);
+    }
+}
@@ -0,0 +1,284 @@
+//! algebraic factorization of numbers of the form (a^n ± b^n)/c, as in the cunningham tables
+
+use std::collections::BTreeMap;
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive};
+
+use crate::arithmetic::cyclotomic::{aurifeuillean_order, aurifeuillean_polynomials, cyclotomic_value, divisors, AURIFEUILLEAN_BOUND};
+use crate::arithmetic::{exact_root, is_perfect_power};
+use crate::error::{Error, Result};
+use crate::expression::Expression;
+use crate::factorization::complete::{factor_completely, factor_completely_with};
+use crate::factorization::lenstra::EcmConfig;
+
+/// the number (a^n + b^n)/c or (a^n - b^n)/c with a > b > 0, where a and b are no perfect powers
+/// of a common exponent, that is n is as large as possible
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpecialForm {
+    /// a, the larger base
+    pub base: BigInt,
+    /// b, one for the common forms a^n ± 1
+    pub other: BigInt,
+    /// n, the largest exponent a^n ± b^n can be written with
+    pub exponent: u32,
+    /// whether the form is a^n + b^n instead of a^n - b^n
+    pub plus: bool,
+    /// c, the constant a^n ± b^n is divided by, one if there is none
+    pub divisor: BigInt,
+}
+
+impl SpecialForm {
+    /// recognizes a^n ± b^m, optionally divided by a constant, from the tree of an expression.
+    /// plain numbers with n ± 1 a perfect power are recognized as well. the bases are reduced
+    /// to roots, so 4^5 - 1 becomes 2^10 - 1, and a^n ± b^m is written with gcd(n, m) as exponent
+    pub fn recognize(expression: &Expression) -> Option<Self> {
+        let (inner, divisor) = match expression {
+            Expression::Divide(inner, divisor) => (&**inner, divisor.evaluate().ok()?),
+            _ => (expression, BigInt::one()),
+        };
+        if !divisor.is_positive() {
+            return None;
+        }
+
+        let binomial = |left: &Expression, right: &Expression, plus| {
+            SpecialForm::from_powers(power(left)?, power(right)?, plus, &divisor)
+        };
+        let structural = match inner {
+            Expression::Add(left, right) => binomial(left, right, true),
+            Expression::Subtract(left, right) => binomial(left, right, false),
+            _ => None,
+        };
+
+        // n + 1 = a^k gives a^k - 1, n - 1 = a^k gives a^k + 1
+        structural.or_else(|| {
+            let value = inner.evaluate().ok()?;
+            let (one, plus) = match is_perfect_power(&(&value + 1u32)) {
+                Some(_) => (BigInt::from(-1), false),
+                None => (BigInt::one(), true),
+            };
+            let (base, exponent) = is_perfect_power(&(value - &one))?;
+            SpecialForm::from_powers((base, exponent), (BigInt::one(), 1), plus, &divisor)
+        })
+    }
+
+    /// builds a^n ± b^m with the largest common exponent, None if it is not of the form
+    fn from_powers(first: (BigInt, u32), second: (BigInt, u32), plus: bool, divisor: &BigInt) -> Option<Self> {
+        let ((a, n), (b, m)) = match plus && first.0.is_one() {
+            true => (root(second), root(first)),
+            false => (root(first), root(second)),
+        };
+        if !a.is_positive() || !b.is_positive() || a.is_one() || a == b || !plus && a < b {
+            return None;
+        }
+
+        let exponent = match b.is_one() {
+            true => n,
+            false => n.gcd(&m),
+        };
+        let form = SpecialForm {
+            base: a.pow(n / exponent),
+            other: match b.is_one() {
+                true => b,
+                false => b.pow(m / exponent),
+            },
+            exponent,
+            plus,
+            divisor: divisor.clone(),
+        };
+
+        match form.binomial().is_multiple_of(&form.divisor) {
+            true => Some(form),
+            false => None,
+        }
+    }
+
+    /// returns a^n ± b^n, the number before the division
+    fn binomial(&self) -> BigInt {
+        let (a, b) = (self.base.pow(self.exponent), self.other.pow(self.exponent));
+        match self.plus {
+            true => a + b,
+            false => a - b,
+        }
+    }
+
+    /// returns the value (a^n ± b^n)/c
+    pub fn value(&self) -> BigInt {
+        self.binomial() / &self.divisor
+    }
+
+    /// splits a^n ± b^n into algebraic factors, whose product it is. a^n - b^n is the product of
+    /// the cyclotomic values Φ_d(a, b) over the divisors d of n, a^n + b^n the one over the d
+    /// that divide 2n but not n. with s the squarefree part of ab, Φ_d(a, b) of order d = s·k or
+    /// d = 2s·k for odd k is split further by its aurifeuillean factors. factors of one are left
+    /// out, the factors are not necessarily prime
+    pub fn algebraic_factors(&self) -> Vec<BigInt> {
+        let n = self.exponent as u64;
+        let orders = match self.plus {
+            true => divisors(2 * n).into_iter().filter(|order| !n.is_multiple_of(*order)).collect(),
+            false => divisors(n),
+        };
+        let squarefree = squarefree_part(&(&self.base * &self.other));
+
+        let mut factors = vec![];
+        for order in orders {
+            let value = cyclotomic_value(order, &self.base, &self.other);
+            match squarefree.and_then(|s| self.aurifeuillean_split(order, s, &value)) {
+                Some((first, second)) => factors.extend([first, second]),
+                None => factors.push(value),
+            }
+        }
+
+        factors.retain(|factor| !factor.is_one());
+        return factors;
+    }
+
+    /// splits the cyclotomic value Φ_d(a, b) with s the squarefree part of ab. for d = n'·k with
+    /// n' the aurifeuillean order of s and k odd, Φ_d(a, b) divides Φ_n'(A, B) with A = a^k and
+    /// B = b^k, where s·A·B = (s·y)² is a square and Φ_n'(A, B) = (C - s·y·D)(C + s·y·D).
+    /// the gcd with the first factor splits off the part of Φ_d(a, b) in it
+    fn aurifeuillean_split(&self, order: u64, s: u64, value: &BigInt) -> Option<(BigInt, BigInt)> {
+        let base_order = aurifeuillean_order(s);
+        if !order.is_multiple_of(base_order) || (order / base_order).is_even() {
+            return None;
+        }
+
+        let k = (order / base_order) as u32;
+        let (a, b) = (self.base.pow(k), self.other.pow(k));
+        let y = exact_root(&(&a * &b / s), 2)?;
+        let (c, d) = aurifeuillean_polynomials(s)?;
+        let (c, d) = (homogeneous(&c, &a, &b), homogeneous(&d, &a, &b));
+
+        let factor = value.gcd(&(c - s * y * d));
+        match factor > BigInt::one() && factor < *value {
+            true => Some((value / &factor, factor)),
+            false => None,
+        }
+    }
+}
+
+/// factorizes the value of an expression like 2^521-1 or (10^71-1)/9 completely. numbers of the
+/// form (a^n ± b^n)/c are split into their algebraic factors first, so only those are factorized
+/// by the general methods, and the factors of c are taken out afterwards
+pub fn factor_expression(input: &str) -> Result<Vec<(BigInt, u32)>, BigInt> {
+    factor_expression_with(input, &EcmConfig::default())
+}
+
+/// factorizes the value of an expression with the given settings of the elliptic curve method
+pub fn factor_expression_with(input: &str, config: &EcmConfig) -> Result<Vec<(BigInt, u32)>, BigInt> {
+    let expression = input.parse::<Expression>()?;
+    let value = expression.evaluate()?;
+
+    let Some(form) = SpecialForm::recognize(&expression) else {
+        return factor_completely_with(value, config);
+    };
+
+    let mut primes = BTreeMap::new();
+    for factor in form.algebraic_factors() {
+        for (prime, exponent) in factor_completely_with(factor, config)? {
+            *primes.entry(prime).or_insert(0) += exponent;
+        }
+    }
+    for (prime, exponent) in factor_completely(form.divisor)? {
+        match primes.get_mut(&prime) {
+            Some(count) if *count >= exponent => *count -= exponent,
+            _ => return Err(Error::InvalidInput("the divisor does not divide the number")),
+        }
+    }
+
+    Ok(primes.into_iter().filter(|(_, exponent)| *exponent > 0).collect())
+}
+
+/// returns (base, exponent) of a power or a plain number, x^0 counts as one
+fn power(expression: &Expression) -> Option<(BigInt, u32)> {
+    match expression {
+        Expression::Number(number) => Some((number.clone(), 1)),
+        Expression::Power(base, exponent) => match exponent.evaluate().ok()?.to_u32()? {
+            0 => Some((BigInt::one(), 1)),
+            exponent => Some((base.evaluate().ok()?, exponent)),
+        },
+        _ => None,
+    }
+}
+
+/// writes a^n with a perfect power a = r^k as r^(kn)
+fn root((base, exponent): (BigInt, u32)) -> (BigInt, u32) {
+    match is_perfect_power(&base) {
+        Some((root, power)) if root.is_positive() => (root, exponent * power),
+        _ => (base, exponent),
+    }
+}
+
+/// returns the squarefree part of a product below 2^64 if it lies in 2..=AURIFEUILLEAN_BOUND
+fn squarefree_part(product: &BigInt) -> Option<u64> {
+    let word = product.to_i128().filter(|word| *word < 1 << 64)?;
+    let part = factor_completely(word).ok()?.into_iter()
+        .filter(|(_, exponent)| exponent % 2 == 1)
+        .map(|(prime, _)| prime)
+        .product::<i128>() as u64;
+
+    match (2..=AURIFEUILLEAN_BOUND).contains(&part) {
+        true => Some(part),
+        false => None,
+    }
+}
+
+/// evaluates the homogeneous form b^deg·f(a/b) of a polynomial, lowest degree first
+fn homogeneous(coefficients: &[BigInt], a: &BigInt, b: &BigInt) -> BigInt {
+    let degree = coefficients.len() as u32 - 1;
+    coefficients.iter().enumerate()
+        .map(|(i, coefficient)| coefficient * a.pow(i as u32) * b.pow(degree - i as u32))
+        .sum()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// recognizes the special form of an expression
+    fn form(input: &str) -> Option<SpecialForm> {
+        SpecialForm::recognize(&input.parse().unwrap())
+    }
+
+    #[test]
+    fn special_forms_are_recognized() {
+        let two = |exponent, plus| Some((BigInt::from(2), BigInt::one(), exponent, plus));
+        let parts = |form: Option<SpecialForm>| form.map(|form| (form.base, form.other, form.exponent, form.plus));
+
+        assert_eq!(parts(form("2^521-1")), two(521, false));
+        assert_eq!(parts(form("1 + 4^5")), two(10, true));
+        assert_eq!(parts(form("1023")), two(10, false));
+        assert_eq!(parts(form("1025")), two(10, true));
+        assert_eq!(parts(form("8^4 - 4^6")), None);
+        assert_eq!(parts(form("3^6 - 2^4")), Some((BigInt::from(27), BigInt::from(4), 2, false)));
+        assert_eq!(parts(form("27^2 - 9^3")), None);
+        assert_eq!(parts(form("7 * 3 + 2")), None);
+        assert_eq!(parts(form("7^2 * 2 + 1")), Some((BigInt::from(10), BigInt::one(), 2, false)));
+
+        let repunit = form("(10^71-1)/9").unwrap();
+        assert_eq!((repunit.exponent, repunit.divisor.clone()), (71, BigInt::from(9)));
+        assert_eq!(repunit.value(), "1".repeat(71).parse::<BigInt>().unwrap());
+        assert_eq!(repunit.algebraic_factors(), vec![BigInt::from(9), repunit.value()]);
+        assert_eq!(form("(2^10-1)/2"), None);
+    }
+
+    #[test]
+    fn algebraic_factors_split_cunningham_numbers() {
+        // 2^58 + 1 = 5·(2^29 - 2^15 + 1)/5·(2^29 + 2^15 + 1) and 3^9 + 1 = 4·7·19·37
+        let factors = form("2^58+1").unwrap().algebraic_factors();
+        assert_eq!(factors, vec![BigInt::from(5), BigInt::from(536_903_681), BigInt::from(107_367_629)]);
+        assert!(form("3^9+1").unwrap().algebraic_factors().contains(&BigInt::from(19)));
+
+        for input in ["2^58+1", "2^60-1", "3^45+1", "(10^18-1)/9", "5^35-2^35", "6^42+1", "12^30+7^30", "7^49+1"] {
+            let form = form(input).unwrap();
+            assert_eq!(form.algebraic_factors().into_iter().product::<BigInt>(), form.binomial(), "{}", input);
+            let expected = factor_completely(form.value()).unwrap();
+            assert_eq!(factor_expression(input).unwrap(), expected, "{}", input);
+        }
+
+        assert_eq!(factor_expression("2^3 * 3").unwrap(), vec![(BigInt::from(2), 3), (BigInt::from(3), 1)]);
+        assert!(matches!(factor_expression("2^"), Err(Error::InvalidInput(_))));
+    }
+}
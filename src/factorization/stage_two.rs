@@ -0,0 +1,383 @@
+use num_integer::Integer;
+
+use crate::arithmetic::polynomial::Polynomial;
+use crate::arithmetic::{ModContext, Number, Reducer};
+use crate::error::{Error, Result};
+use crate::factorization::lenstra::StageTwoMethod;
+use crate::points::{ChudnovskyPoint, EdwardsPoint, MontgomeryPoint};
+use crate::sieve::Primes;
+
+/// giant step sizes D to choose from with their totient, primorials and their doubles keep the
+/// number of baby steps φ(D)/2 small
+const GIANT_STEPS: [(u64, u64); 12] = [
+    (6, 2), (30, 8), (60, 16), (210, 48), (420, 96), (2_310, 480), (4_620, 960), (30_030, 5_760),
+    (60_060, 11_520), (510_510, 92_160), (1_021_020, 184_320), (9_699_690, 1_658_880),
+];
+
+/// largest B2 the automatic choice runs the pairing for. above it the comparisons of the pairs
+/// cost more than the product and remainder trees
+const MAX_PAIRING_B2: u64 = 100_000;
+
+/// point arithmetic the stage 2 continuation needs. every multiple is reduced to the fraction
+/// of a coordinate that P and -P share, so a single comparison covers m·D + j and m·D - j
+pub(crate) trait Continuation<T: Number> {
+    /// returns the coordinate fractions (numerator, denominator) of (start + i·step)·P for i in
+    /// 0..count, start and step must be positive
+    fn progression(&self, start: u64, step: u64, count: usize) -> Vec<(T, T)>;
+
+    /// returns the coordinate fraction of the point in infinity, which stands for 0·D·P
+    fn infinity(&self) -> (T, T);
+
+    /// returns the context the coordinates are encoded in
+    fn context(&self) -> &ModContext<T>;
+}
+
+impl<T: Number> Continuation<T> for MontgomeryPoint<T> {
+    fn progression(&self, start: u64, step: u64, count: usize) -> Vec<(T, T)> {
+        // every sum knows its difference, the multiple before the previous one
+        let step_point = self.ladder(&(step as i64));
+        let mut previous = self.ladder(&(start as i64));
+        let mut current = self.ladder(&((start + step) as i64));
+        let mut fractions = vec![];
+
+        for _ in 0..count {
+            fractions.push((previous.x.clone(), previous.z.clone()));
+
+            let next = current.differential_add(&step_point, &previous);
+            previous = std::mem::replace(&mut current, next);
+        }
+
+        return fractions;
+    }
+
+    fn infinity(&self) -> (T, T) {
+        let infinity = MontgomeryPoint::new_infinite(self.curve.clone());
+        (infinity.x, infinity.z)
+    }
+
+    fn context(&self) -> &ModContext<T> {
+        &self.curve.context
+    }
+}
+
+impl<T: Number> Continuation<T> for ChudnovskyPoint<T> {
+    fn progression(&self, start: u64, step: u64, count: usize) -> Vec<(T, T)> {
+        // x = X/Z² is shared by P and -P
+        let step_point = self.multiply(&(step as i64));
+        let mut current = self.multiply(&(start as i64));
+        let mut fractions = vec![];
+
+        for _ in 0..count {
+            fractions.push((current.x.clone(), current.zz.clone()));
+            current = current.add_point(&step_point);
+        }
+
+        return fractions;
+    }
+
+    fn infinity(&self) -> (T, T) {
+        let infinity = ChudnovskyPoint::new_infinite(self.curve.clone());
+        (infinity.x, infinity.zz)
+    }
+
+    fn context(&self) -> &ModContext<T> {
+        &self.curve.context
+    }
+}
+
+impl<T: Number> Continuation<T> for EdwardsPoint<T> {
+    fn progression(&self, start: u64, step: u64, count: usize) -> Vec<(T, T)> {
+        // -(x, y) = (-x, y), so y = Y/Z is shared by P and -P
+        let step_point = self.multiply(&(step as i64));
+        let mut current = self.multiply(&(start as i64));
+        let mut fractions = vec![];
+
+        for _ in 0..count {
+            fractions.push((current.y.clone(), current.z.clone()));
+            current = current.add_point(&step_point);
+        }
+
+        return fractions;
+    }
+
+    fn infinity(&self) -> (T, T) {
+        // the neutral element (0, 1) is the only point with y = 1
+        let infinity = EdwardsPoint::new_infinite(self.curve.clone());
+        (infinity.y, infinity.z)
+    }
+
+    fn context(&self) -> &ModContext<T> {
+        &self.curve.context
+    }
+}
+
+/// planned stage 2, which only depends on B1 and B2, so every curve shares it
+pub(crate) enum StageTwo {
+    Pairing(Pairing),
+    Polynomial(PolynomialEvaluation),
+}
+
+impl StageTwo {
+    /// plans the continuation from B1 to B2 with the given method
+    pub(crate) fn new(b1: u64, b2: u64, method: StageTwoMethod) -> Self {
+        match method {
+            StageTwoMethod::Pairing => StageTwo::Pairing(Pairing::new(b1, b2)),
+            StageTwoMethod::Polynomial => StageTwo::Polynomial(PolynomialEvaluation::new(b1, b2)),
+            StageTwoMethod::Auto => match b2 <= MAX_PAIRING_B2 {
+                true => StageTwo::Pairing(Pairing::new(b1, b2)),
+                false => StageTwo::Polynomial(PolynomialEvaluation::new(b1, b2)),
+            },
+        }
+    }
+
+    /// runs the continuation on the stage 1 result, returns a number whose gcd with the
+    /// modulus reveals the factor
+    pub(crate) fn run<T: Number, P: Continuation<T>>(&self, point: &P) -> T {
+        match self {
+            StageTwo::Pairing(pairing) => pairing.run(point),
+            StageTwo::Polynomial(evaluation) => evaluation.run(point),
+        }
+    }
+}
+
+/// pairs of a giant and a baby step the baby-step giant-step continuation compares
+pub(crate) struct Pairing {
+    step: u64,
+    babies: usize,
+    first: u64,
+    pairs: Vec<Vec<usize>>,
+}
+
+impl Pairing {
+    /// plans the continuation from B1 to B2. every prime q in (B1, B2] is written as m·D ± j
+    /// with j odd and at most D/2, and m·D + j and m·D - j mark the same pair. the primes
+    /// below D/2 are the pairs of m = 0, whose giant step is the point in infinity
+    pub(crate) fn new(b1: u64, b2: u64) -> Self {
+        let step = pairing_step(b1, b2);
+        let (first, last) = ((b1 + step / 2) / step, (b2 + step / 2) / step);
+
+        let mut marked = vec![vec![false; (step / 2 + 1) as usize / 2]; (last - first + 1) as usize];
+        for prime in Primes::between(b1 + 1, b2) {
+            let m = (prime + step / 2) / step;
+            marked[(m - first) as usize][(prime.abs_diff(m * step) / 2) as usize] = true;
+        }
+
+        // the index i stands for the baby step j = 2i + 1, which is coprime to D above m = 0
+        let pairs = marked.into_iter().map(|row| {
+            row.into_iter().enumerate()
+                .filter(|(_, marked)| *marked)
+                .map(|(index, _)| index)
+                .collect()
+        }).collect();
+
+        Pairing { step, babies: (step / 2 + 1) as usize / 2, first, pairs }
+    }
+
+    /// runs the baby-step giant-step continuation on the stage 1 result Q. Q has order q
+    /// modulo a prime divisor iff the coordinates of m·D·Q and j·Q agree there, so all
+    /// comparisons are accumulated into one product, whose gcd with the modulus is taken once
+    pub(crate) fn run<T: Number, P: Continuation<T>>(&self, point: &P) -> T {
+        let c = point.context();
+
+        // the progressions cannot start at the point in infinity, so m = 0 is prepended
+        let babies = point.progression(1, 2, self.babies);
+        let giants = match self.first {
+            0 => std::iter::once(point.infinity())
+                .chain(point.progression(self.step, self.step, self.pairs.len() - 1))
+                .collect(),
+            _ => point.progression(self.first * self.step, self.step, self.pairs.len()),
+        };
+
+        let mut product = c.one();
+        for (giant, pairs) in giants.iter().zip(&self.pairs) {
+            for baby in pairs.iter().map(|index| &babies[*index]) {
+                let difference = c.sub(&c.mul(&giant.0, &baby.1), &c.mul(&baby.0, &giant.1));
+                product = c.mul(&product, &difference);
+            }
+        }
+
+        return c.decode(&product);
+    }
+}
+
+/// giant steps of the fast polynomial continuation, which compares every giant step with
+/// every baby step coprime to D instead of only the pairs that cover a prime
+pub(crate) struct PolynomialEvaluation {
+    step: u64,
+    babies: usize,
+    first: u64,
+    last: u64,
+    small: Vec<usize>,
+}
+
+impl PolynomialEvaluation {
+    /// plans the continuation from B1 to B2, it only needs the primes up to D/2. the giant
+    /// steps start at D, the primes in (B1, D/2) are the baby steps that meet the point in infinity
+    pub(crate) fn new(b1: u64, b2: u64) -> Self {
+        let step = polynomial_step(b1, b2);
+        let (first, last) = (((b1 + step / 2) / step).max(1), (b2 + step / 2) / step);
+        let small = Primes::between(b1 + 1, b2.min(step / 2)).map(|prime| (prime / 2) as usize).collect();
+
+        PolynomialEvaluation { step, babies: (step / 2 + 1) as usize / 2, first, last, small }
+    }
+
+    /// runs the continuation of GMP-ECM on the stage 1 result Q. F(X) = ∏ (X - x(j·Q)) is built
+    /// from the baby steps with a product tree and evaluated at blocks of giant steps x(m·D·Q)
+    /// with remainder trees, so the product of all values is ∏ (x(m·D·Q) - x(j·Q))
+    pub(crate) fn run<T: Number, P: Continuation<T>>(&self, point: &P) -> T {
+        let c = point.context();
+        let babies = point.progression(1, 2, self.babies);
+
+        // the primes below D/2 are compared with 0·D, just as the pairing does
+        let infinity = point.infinity();
+        let mut product = c.one();
+        for baby in self.small.iter().map(|index| &babies[*index]) {
+            let difference = c.sub(&c.mul(&infinity.0, &baby.1), &c.mul(&baby.0, &infinity.1));
+            product = c.mul(&product, &difference);
+        }
+
+        let babies: Vec<(T, T)> = babies.into_iter().enumerate()
+            .filter(|(index, _)| (2 * *index as u64 + 1).gcd(&self.step) == 1)
+            .map(|(_, baby)| baby)
+            .collect();
+        let roots = match normalize(&babies, c) {
+            Ok(roots) => roots,
+            Err(divisor) => return divisor,
+        };
+        let polynomial = Polynomial::from_roots(&roots, c.modulus());
+
+        // blocks as large as the degree keep every remainder tree balanced
+        let mut m = self.first;
+        while m <= self.last {
+            let count = (self.last - m + 1).min(roots.len().max(1) as u64);
+            let giants = match normalize(&point.progression(m * self.step, self.step, count as usize), c) {
+                Ok(giants) => giants,
+                Err(divisor) => return divisor,
+            };
+
+            for value in polynomial.evaluate(&giants, c.modulus()) {
+                product = c.mul(&product, &c.encode(&value));
+            }
+            m += count;
+        }
+
+        return c.decode(&product);
+    }
+}
+
+/// returns the decoded quotients of the fractions with a single inversion (montgomery's trick).
+/// fails with the divisor the product of the denominators shares with the modulus
+fn normalize<T: Number>(fractions: &[(T, T)], c: &ModContext<T>) -> std::result::Result<Vec<T>, T> {
+    // prefix products of the denominators
+    let mut prefixes = Vec::with_capacity(fractions.len());
+    let mut product = c.one();
+    for (_, denominator) in fractions {
+        prefixes.push(product.clone());
+        product = c.mul(&product, denominator);
+    }
+
+    let mut inverse = match invert(&product, c) {
+        Ok(inverse) => inverse,
+        Err(Error::NotInvertible { gcd }) => return Err(gcd),
+        Err(_) => return Err(T::one()),
+    };
+
+    // walk back, the running inverse drops one denominator per step
+    let mut quotients = vec![T::zero(); fractions.len()];
+    for (index, (numerator, denominator)) in fractions.iter().enumerate().rev() {
+        quotients[index] = c.decode(&c.mul(numerator, &c.mul(&inverse, &prefixes[index])));
+        inverse = c.mul(&inverse, denominator);
+    }
+
+    Ok(quotients)
+}
+
+/// inverts an encoded number, zero shares the whole modulus
+fn invert<T: Number>(number: &T, c: &ModContext<T>) -> Result<T, T> {
+    match number.is_zero() {
+        true => Err(Error::NotInvertible { gcd: c.modulus().clone() }),
+        false => c.inv(number),
+    }
+}
+
+/// returns the giant step D of the pairing that minimizes the point operations
+/// φ(D)/2 + (B2 - B1)/D, independent of B1
+fn pairing_step(b1: u64, b2: u64) -> u64 {
+    GIANT_STEPS.iter()
+        .min_by_key(|(step, totient)| totient / 2 + (b2 - b1) / step)
+        .map(|(step, _)| *step)
+        .unwrap_or(GIANT_STEPS[0].0)
+}
+
+/// returns the giant step D of the polynomial continuation with the lowest cost. it takes D/4
+/// baby and (B2 - B1)/D giant steps, and the k = φ(D)/2 roots cost about k·log²(k) for the
+/// product tree and for every remainder tree of a block of k giant steps, so D grows like √B2
+fn polynomial_step(b1: u64, b2: u64) -> u64 {
+    GIANT_STEPS.iter()
+        .min_by_key(|(step, totient)| {
+            let (giants, degree) = ((b2 - b1) / step + 1, totient / 2);
+            let logarithm = (u64::BITS - degree.leading_zeros()) as u64;
+            step / 4 + giants + (giants.div_ceil(degree) + 1) * degree * logarithm * logarithm
+        })
+        .map(|(step, _)| *step)
+        .unwrap_or(GIANT_STEPS[0].0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::factorization::lenstra::prime_powers;
+
+    /// runs stage 1 on the montgomery curve selected by sigma
+    fn stage_one(sigma: i64, context: &Arc<ModContext<i64>>, b1: u64) -> Option<MontgomeryPoint<i64>> {
+        let point = MontgomeryPoint::with_suyama_sigma(&sigma, context.clone()).ok()?;
+        Some(prime_powers(b1).fold(point, |point, power| point.ladder(&(power as i64))))
+    }
+
+    /// returns the stage 1 results of the montgomery curves selected by sigma modulo n = p·q
+    /// together with the order of the point modulo p, counted by adding it until it vanishes
+    fn stage_one_orders(p: i64, q: i64, b1: u64, sigmas: std::ops::Range<i64>) -> Vec<(MontgomeryPoint<i64>, i64)> {
+        let (context, prime) = (Arc::new(ModContext::new(&(p * q))), Arc::new(ModContext::new(&p)));
+
+        sigmas.filter_map(|sigma| {
+            let (point, reduced) = (stage_one(sigma, &context, b1)?, stage_one(sigma, &prime, b1)?);
+            let multiples = reduced.progression(1, 1, (p + 1 + 2 * (p as f64).sqrt() as i64) as usize);
+            let order = multiples.iter().position(|(_, z)| *z == 0)? as i64 + 1;
+            Some((point, order))
+        }).collect()
+    }
+
+    #[test]
+    fn pairing_catches_the_prime_orders_below_half_the_giant_step() {
+        let (p, b1, b2) = (1_009i64, 10, 1_000);
+        let pairing = Pairing::new(b1, b2);
+        assert!(pairing.step / 2 > b1);
+
+        // every prime order in (B1, B2] is caught, the ones below D/2 by the giant step 0·D
+        let primes = stage_one_orders(p, 1_000_003, b1, 6..100).into_iter()
+            .filter(|(_, order)| *order as u64 > b1 && *order as u64 <= b2 && crate::primality::is_prime(order))
+            .collect::<Vec<_>>();
+        assert!(primes.iter().any(|(_, order)| (*order as u64) < pairing.step / 2));
+        for (point, order) in primes {
+            assert_eq!(pairing.run(&point) % p, 0, "order {}", order);
+        }
+    }
+
+    #[test]
+    fn polynomial_evaluation_reaches_a_b2_beyond_a_billion() {
+        let (p, b1) = (12_000_000_073i64, 50);
+        let context = Arc::new(ModContext::new(&p));
+
+        // D grows with √B2 instead of B1, the baby steps and blocks stay small
+        let (evaluation, narrow) = (PolynomialEvaluation::new(b1, 1_000_000_000), PolynomialEvaluation::new(b1, 990_000_000));
+        assert!(evaluation.step > 2_310 && evaluation.babies < 100_000);
+
+        // stage 1 leaves the curve of sigma 144 with the prime order 999_994_823 modulo p
+        let (point, order) = (stage_one(144, &context, b1).unwrap(), 999_994_823i64);
+        assert!(!point.is_infinite() && point.ladder(&order).is_infinite());
+        assert_eq!(evaluation.run(&point), 0);
+        assert_ne!(narrow.run(&point), 0);
+    }
+}
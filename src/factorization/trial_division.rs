@@ -0,0 +1,50 @@
+use crate::arithmetic::Number;
+use crate::sieve::Primes;
+
+/// divides the primes up to a bound out of the absolute value of a number, returns the pairs
+/// (prime, exponent) found in ascending order and the remaining cofactor
+pub fn trial_division<T: Number>(number: &T, bound: u64) -> (Vec<(T, u32)>, T) {
+    let mut remaining = number.abs();
+    let mut factors = vec![];
+
+    for prime in Primes::up_to(bound).map(|prime| T::from(prime as i64)) {
+        // a cofactor below p² is one or a prime
+        if prime.clone() * prime.clone() > remaining {
+            if remaining > T::one() && remaining <= T::from(bound as i64) {
+                factors.push((std::mem::replace(&mut remaining, T::one()), 1));
+            }
+            break;
+        }
+
+        let mut exponent = 0;
+        while remaining.is_multiple_of(&prime) {
+            remaining = remaining.div_floor(&prime);
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((prime, exponent));
+        }
+    }
+
+    return (factors, remaining);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigInt;
+
+    #[test]
+    fn small_factors_are_stripped() {
+        assert_eq!(trial_division(&360i64, 10), (vec![(2, 3), (3, 2), (5, 1)], 1));
+        assert_eq!(trial_division(&-360i64, 3), (vec![(2, 3), (3, 2)], 5));
+        assert_eq!(trial_division(&(97 * 1_000_003i64), 100), (vec![(97, 1)], 1_000_003));
+        assert_eq!(trial_division(&97i64, 100), (vec![(97, 1)], 1));
+        assert_eq!(trial_division(&97i64, 50), (vec![], 97));
+        assert_eq!(trial_division(&1i64, 100), (vec![], 1));
+
+        let number = BigInt::from(3).pow(5) * BigInt::from(1_000_003).pow(2);
+        assert_eq!(trial_division(&number, 1_000), (vec![(BigInt::from(3), 5)], BigInt::from(1_000_003).pow(2)));
+    }
+}
@@ -0,0 +1,28 @@
+//! Lenstra elliptic-curve factorization and the modular arithmetic and curves it runs on.
+//!
+//! Everything is generic over [`Number`], which is implemented for `i64`, `i128` and
+//! [`num_bigint::BigInt`], so numbers of any size can be factorized.
+
+#![allow(clippy::needless_return)]
+
+pub mod arithmetic;
+pub mod curves;
+pub mod error;
+pub mod expression;
+pub mod factorization;
+pub mod points;
+pub mod primality;
+pub mod sieve;
+
+pub use arithmetic::{exact_root, is_perfect_power, Number};
+pub use curves::{MontgomeryCurve, TwistedEdwards, WeierStrass};
+pub use error::Error;
+pub use expression::Expression;
+pub use factorization::{
+    ecm, ecm_curve, factor_completely, factor_completely_with, factor_expression, factor_expression_with, factorize, factorize_with,
+    hart_olf, lehman, pollard_pm1, pollard_rho, siqs, small_factor, squfof, trial_division, williams_pp1, EcmConfig, EcmFactor,
+    Engine, SpecialForm, StageTwoMethod,
+};
+pub use points::{ChudnovskyPoint, EdwardsPoint, JacobianPoint, MontgomeryPoint, ProjectivePoint, PseudoPoint, WeierStrassPoint};
+pub use primality::is_prime;
+pub use sieve::Primes;
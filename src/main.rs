@@ -0,0 +1,31 @@
+use std::{env, process};
+
+use ecc_collection::factor_expression;
+
+fn main() {
+    // the argument may be an expression like 2^67-1 or (10^31-1)/9, special forms are split
+    // into their algebraic factors before the general methods run
+    let Some(input) = env::args().nth(1) else {
+        eprintln!("usage: lenstra <number or expression>");
+        process::exit(2);
+    };
+
+    match factor_expression(&input) {
+        Ok(primes) => {
+            let factors = primes.iter()
+                .map(|(prime, exponent)| match exponent {
+                    1 => prime.to_string(),
+                    _ => format!("{}^{}", prime, exponent),
+                })
+                .collect::<Vec<_>>();
+            match factors.is_empty() {
+                true => println!("{} = 1", input),
+                false => println!("{} = {}", input, factors.join(" * ")),
+            }
+        }
+        Err(error) => {
+            eprintln!("No factors found! {}", error);
+            process::exit(1);
+        }
+    }
+}
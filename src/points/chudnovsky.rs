@@ -0,0 +1,226 @@
+use std::ops;
+
+use crate::arithmetic::{Number, Reducer};
+use crate::curves::WeierStrass;
+use crate::error::Result;
+use crate::points::WeierStrassPoint;
+
+/// point on a weierstrass curve in chudnovsky coordinates (X : Y : Z : Z² : Z³), jacobian
+/// coordinates that carry Z² and Z³ along, so an addition saves their squarings.
+/// coordinates are kept in the form of the curve's context
+#[derive(Clone)]
+pub struct ChudnovskyPoint<T: Number> {
+    pub(crate) x: T,
+    pub(crate) y: T,
+    pub(crate) z: T,
+    pub(crate) zz: T,
+    pub(crate) zzz: T,
+    pub(crate) curve: WeierStrass<T>,
+}
+
+impl<T: Number> ChudnovskyPoint<T> {
+    /// creates a point from its jacobian coordinates
+    pub fn new(x: T, y: T, z: T, curve: WeierStrass<T>) -> Self {
+        let context = &curve.context;
+        let z = context.encode(&z);
+        let zz = context.square(&z);
+
+        ChudnovskyPoint {
+            x: context.encode(&x),
+            y: context.encode(&y),
+            zzz: context.mul(&zz, &z),
+            zz,
+            z,
+            curve,
+        }
+    }
+
+    /// creates the point in infinity (1 : 1 : 0 : 0 : 0)
+    pub fn new_infinite(curve: WeierStrass<T>) -> Self {
+        ChudnovskyPoint {
+            x: curve.context.one(),
+            y: curve.context.one(),
+            z: T::zero(),
+            zz: T::zero(),
+            zzz: T::zero(),
+            curve,
+        }
+    }
+
+    /// converts an affine point, which needs no inversion
+    pub fn from_affine(point: &WeierStrassPoint<T>) -> Self {
+        let one = point.curve.context.one();
+
+        match point.is_infinite() {
+            true => ChudnovskyPoint::new_infinite(point.curve.clone()),
+            false => ChudnovskyPoint {
+                x: point.x.clone(),
+                y: point.y.clone(),
+                z: one.clone(),
+                zz: one.clone(),
+                zzz: one,
+                curve: point.curve.clone(),
+            },
+        }
+    }
+
+    /// converts the point to affine coordinates with one inversion of Z³.
+    /// fails with the shared divisor if Z is not invertible modulo a composite p
+    pub fn to_affine(&self) -> Result<WeierStrassPoint<T>, T> {
+        if self.is_infinite() {
+            return Ok(WeierStrassPoint::new_infinite(self.curve.clone()));
+        }
+
+        // 1/Z² = Z/Z³ and 1/Z³ are both taken from the inverse of Z³
+        let context = &self.curve.context;
+        let zzz_inverse = context.inv(&self.zzz)?;
+        Ok(WeierStrassPoint {
+            x: context.mul(&self.x, &context.mul(&self.z, &zzz_inverse)),
+            y: context.mul(&self.y, &zzz_inverse),
+            y_infinite: false,
+            curve: self.curve.clone(),
+        })
+    }
+
+    /// returns whether this is the point in infinity
+    pub fn is_infinite(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    /// returns the decoded jacobian coordinates (X, Y, Z)
+    pub fn coordinates(&self) -> (T, T, T) {
+        let context = &self.curve.context;
+        (context.decode(&self.x), context.decode(&self.y), context.decode(&self.z))
+    }
+
+    /// returns the curve the point lies on
+    pub fn curve(&self) -> &WeierStrass<T> {
+        &self.curve
+    }
+
+    /// builds a point from X, Y and Z and completes the cached powers of Z
+    fn from_jacobian(x: T, y: T, z: T, curve: WeierStrass<T>) -> Self {
+        let zz = curve.context.square(&z);
+        let zzz = curve.context.mul(&zz, &z);
+        ChudnovskyPoint { x, y, z, zz, zzz, curve }
+    }
+
+    /// doubles the point with the cached Z², a point of order two or the point in infinity
+    /// yield Z = 0, so no special case is needed
+    pub fn double(&self) -> ChudnovskyPoint<T> {
+        let c = &self.curve.context;
+
+        let xx = c.square(&self.x);
+        let yy = c.square(&self.y);
+
+        // S = 4XY², M = 3X² + aZ⁴
+        let s = c.mul(&self.x, &yy);
+        let s = c.add(&s, &s);
+        let s = c.add(&s, &s);
+        let m = c.add(&c.add(&c.add(&xx, &xx), &xx), &c.mul(&self.curve.a, &c.square(&self.zz)));
+
+        let x3 = c.sub(&c.square(&m), &c.add(&s, &s));
+        let yyyy8 = c.square(&yy);
+        let yyyy8 = c.add(&yyyy8, &yyyy8);
+        let yyyy8 = c.add(&yyyy8, &yyyy8);
+        let yyyy8 = c.add(&yyyy8, &yyyy8);
+        let y3 = c.sub(&c.mul(&m, &c.sub(&s, &x3)), &yyyy8);
+        let z3 = c.mul(&self.y, &self.z);
+        let z3 = c.add(&z3, &z3);
+
+        ChudnovskyPoint::from_jacobian(x3, y3, z3, self.curve.clone())
+    }
+
+    /// adds a point of the same curve with the cached powers of Z. the exceptions of the
+    /// formula are handled explicitly: the identity, doubling and adding the inverse point
+    pub(crate) fn add_point(&self, other: &ChudnovskyPoint<T>) -> ChudnovskyPoint<T> {
+        if self.is_infinite() {
+            return other.clone();
+        }
+        if other.is_infinite() {
+            return self.clone();
+        }
+
+        let c = &self.curve.context;
+        let u1 = c.mul(&self.x, &other.zz);
+        let u2 = c.mul(&other.x, &self.zz);
+        let s1 = c.mul(&self.y, &other.zzz);
+        let s2 = c.mul(&other.y, &self.zzz);
+
+        // H = U2 - U1, r = S2 - S1
+        let h = c.sub(&u2, &u1);
+        let r = c.sub(&s2, &s1);
+        if h.is_zero() {
+            return match r.is_zero() {
+                true => self.double(),
+                false => ChudnovskyPoint::new_infinite(self.curve.clone()),
+            };
+        }
+
+        let hh = c.square(&h);
+        let hhh = c.mul(&h, &hh);
+        let v = c.mul(&u1, &hh);
+
+        let x3 = c.sub(&c.sub(&c.square(&r), &hhh), &c.add(&v, &v));
+        let y3 = c.sub(&c.mul(&r, &c.sub(&v, &x3)), &c.mul(&s1, &hhh));
+        let z3 = c.mul(&c.mul(&self.z, &other.z), &h);
+
+        ChudnovskyPoint::from_jacobian(x3, y3, z3, self.curve.clone())
+    }
+}
+
+
+impl<T: Number> PartialEq for ChudnovskyPoint<T> {
+    fn eq(&self, other: &ChudnovskyPoint<T>) -> bool {
+        // compare (X1/Z1², Y1/Z1³) and (X2/Z2², Y2/Z2³) without inverting
+        let c = &self.curve.context;
+        match (self.is_infinite(), other.is_infinite()) {
+            (true, true) => self.curve == other.curve,
+            (false, false) => {
+                self.curve == other.curve &&
+                    c.mul(&self.x, &other.zz) == c.mul(&other.x, &self.zz) &&
+                    c.mul(&self.y, &other.zzz) == c.mul(&other.y, &self.zzz)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<T: Number> ops::Neg for ChudnovskyPoint<T> {
+    type Output = ChudnovskyPoint<T>;
+
+    fn neg(self) -> Self::Output {
+        ChudnovskyPoint { y: self.curve.context.neg(&self.y), ..self }
+    }
+}
+
+impl_projective_ops!(ChudnovskyPoint);
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::points::tests::{groups, pick, PRIMES};
+    use rand::Rng;
+
+    #[test]
+    fn chudnovsky_arithmetic_matches_affine() {
+        let mut rng = rand::thread_rng();
+
+        for points in PRIMES.into_iter().flat_map(groups) {
+            for _ in 0..50 {
+                let (p, q) = (pick(&mut rng, &points), pick(&mut rng, &points));
+                let (chudnovsky_p, chudnovsky_q) = (ChudnovskyPoint::from_affine(&p), ChudnovskyPoint::from_affine(&q));
+
+                let sum = (chudnovsky_p.clone() + chudnovsky_q.clone()).unwrap();
+                assert!(sum.to_affine().unwrap() == (p.clone() + q.clone()).unwrap());
+                assert!(sum == ChudnovskyPoint::from_affine(&(p.clone() + q.clone()).unwrap()));
+                assert!((chudnovsky_p.clone() - chudnovsky_q).unwrap().to_affine().unwrap() == (p.clone() - q).unwrap());
+                assert!(chudnovsky_p.double().to_affine().unwrap() == p.double().unwrap());
+
+                let k = rng.gen_range(-20..20);
+                assert!((k * chudnovsky_p).to_affine().unwrap() == (p * k).unwrap());
+            }
+        }
+    }
+}
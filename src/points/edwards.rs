@@ -0,0 +1,346 @@
+use std::ops;
+use std::sync::Arc;
+
+use crate::arithmetic::{ModContext, Number, Reducer};
+use crate::curves::{TwistedEdwards, WeierStrass};
+use crate::error::{Error, Result};
+use crate::points::{invert_parameter, WeierStrassPoint};
+
+/// point on a twisted edwards curve in extended coordinates (X : Y : Z : T), representing
+/// the affine point (X/Z, Y/Z) with T = XY/Z. coordinates are kept in the form of the curve's context
+#[derive(Clone)]
+pub struct EdwardsPoint<T: Number> {
+    pub(crate) x: T,
+    pub(crate) y: T,
+    pub(crate) z: T,
+    pub(crate) t: T,
+    pub(crate) curve: TwistedEdwards<T>,
+}
+
+impl<T: Number> EdwardsPoint<T> {
+    /// creates a point from its affine coordinates
+    pub fn new(x: T, y: T, curve: TwistedEdwards<T>) -> Self {
+        let context = &curve.context;
+        let (x, y) = (context.encode(&x), context.encode(&y));
+
+        EdwardsPoint {
+            t: context.mul(&x, &y),
+            z: context.one(),
+            x,
+            y,
+            curve,
+        }
+    }
+
+    /// creates the neutral element (0 : 1 : 1 : 0), which takes the role of the point in infinity
+    pub fn new_infinite(curve: TwistedEdwards<T>) -> Self {
+        EdwardsPoint {
+            x: T::zero(),
+            y: curve.context.one(),
+            z: curve.context.one(),
+            t: T::zero(),
+            curve,
+        }
+    }
+
+    /// creates a point on a curve of the z/12 torsion family, where k selects the curve.
+    /// with (X, w) = k·(1, 2) on w² = X³ + 3X and a = X/3, the montgomery curve
+    /// y² = x³ + A·x² + x with A = (-3a⁴ - 6a² + 1)/4a³ has torsion z/12 and the point
+    /// x = (3a² + 1)/4a, y = (a² - 1)·w/24a³, which map to the edwards curve (A + 2, A - 2).
+    /// fails with the shared divisor if an inversion modulo a composite p fails, and with
+    /// SingularCurve for the few degenerate k
+    pub fn with_z12_torsion(k: &T, context: Arc<ModContext<T>>) -> Result<EdwardsPoint<T>, T> {
+        let c = &context;
+        let number = |value: i64| c.encode(&T::from(value));
+        let invert = |value: &T| invert_parameter(c, value);
+
+        // parameter point on the auxiliary curve of rank one
+        let auxiliary = WeierStrass::with_context(T::from(3), T::zero(), context.clone())?;
+        let parameter = WeierStrassPoint::new(T::one(), T::from(2), auxiliary).multiply(k)?;
+        if parameter.is_infinite() {
+            return Err(Error::SingularCurve);
+        }
+
+        // a = X/3 and the powers of it the montgomery curve needs
+        let a = c.mul(&parameter.x, &invert(&number(3))?);
+        let a_squared = c.square(&a);
+        let a_cubed = c.mul(&a_squared, &a);
+
+        // A = (-3a⁴ - 6a² + 1)/4a³
+        let numerator = c.sub(&number(1), &c.add(&c.mul(&number(3), &c.square(&a_squared)), &c.mul(&number(6), &a_squared)));
+        let montgomery_a = c.mul(&numerator, &invert(&c.mul(&number(4), &a_cubed))?);
+
+        // x = (3a² + 1)/4a, y = (a² - 1)·w/24a³
+        let x = c.mul(&c.add(&c.mul(&number(3), &a_squared), &number(1)), &invert(&c.mul(&number(4), &a))?);
+        let y = c.mul(&c.mul(&c.sub(&a_squared, &number(1)), &parameter.y), &invert(&c.mul(&number(24), &a_cubed))?);
+
+        // birational map to the edwards curve: (x, y) -> (x/y, (x - 1)/(x + 1))
+        let edwards_x = c.mul(&x, &invert(&y)?);
+        let edwards_y = c.mul(&c.sub(&x, &number(1)), &invert(&c.add(&x, &number(1)))?);
+
+        let curve = TwistedEdwards::with_context(
+            c.decode(&c.add(&montgomery_a, &number(2))),
+            c.decode(&c.sub(&montgomery_a, &number(2))),
+            context.clone(),
+        )?;
+        Ok(EdwardsPoint::new(c.decode(&edwards_x), c.decode(&edwards_y), curve))
+    }
+
+    /// creates a point on an a = -1 curve of the z/2 × z/4 torsion family, where m selects the
+    /// curve. with e = 4m²(1 - m⁴)/(1 + 8m⁴), the curve -x² + y² = 1 - e⁴x²y² has torsion
+    /// z/2 × z/4 and is Y² = X(X - (1 - e²)²)(X - (1 + e²)²) in weierstrass form, which has
+    /// the point X = w² for w = e² - e/2m² + 1. a = -1 curves with z/12 torsion do not exist,
+    /// their points of order four would be (±√-1, 0). fails with the shared divisor if an
+    /// inversion modulo a composite p fails, and with SingularCurve for the few degenerate m
+    pub fn with_z2z4_torsion(m: &T, context: Arc<ModContext<T>>) -> Result<EdwardsPoint<T>, T> {
+        let c = &context;
+        let number = |value: i64| c.encode(&T::from(value));
+        let invert = |value: &T| invert_parameter(c, value);
+
+        let m = c.encode(m);
+        let m_squared = c.square(&m);
+        let m_fourth = c.square(&m_squared);
+
+        // e = 4m²(1 - m⁴)/(1 + 8m⁴)
+        let numerator = c.mul(&c.mul(&number(4), &m_squared), &c.sub(&number(1), &m_fourth));
+        let e = c.mul(&numerator, &invert(&c.add(&number(1), &c.mul(&number(8), &m_fourth)))?);
+        let e_squared = c.square(&e);
+        let e_fourth = c.square(&e_squared);
+
+        // w = e² - e/2m² + 1 solves (1 - e²)² - w² = e(e/m - m)²
+        let w = c.add(&c.sub(&e_squared, &c.mul(&e, &invert(&c.add(&m_squared, &m_squared))?)), &number(1));
+        let w_squared = c.square(&w);
+
+        // x = 2X/Y = 2m²w/e(e² - m⁴), y = (X - e⁴ + 1)/(X + e⁴ - 1)
+        let x = c.mul(&c.mul(&number(2), &c.mul(&m_squared, &w)), &invert(&c.mul(&e, &c.sub(&e_squared, &m_fourth)))?);
+        let y = c.mul(&c.add(&c.sub(&w_squared, &e_fourth), &number(1)), &invert(&c.sub(&c.add(&w_squared, &e_fourth), &number(1)))?);
+
+        let curve = TwistedEdwards::with_context(c.decode(&c.neg(&number(1))), c.decode(&c.neg(&e_fourth)), context.clone())?;
+        Ok(EdwardsPoint::new(c.decode(&x), c.decode(&y), curve))
+    }
+
+    /// returns whether this is the neutral element
+    pub fn is_infinite(&self) -> bool {
+        self.x.is_zero() && self.y == self.z
+    }
+
+    /// returns whether the point satisfies a·X² + Y² = Z² + d·T² and XY = ZT
+    pub fn is_on_curve(&self) -> bool {
+        let c = &self.curve.context;
+        let left = c.add(&c.mul(&self.curve.a, &c.square(&self.x)), &c.square(&self.y));
+        let right = c.add(&c.square(&self.z), &c.mul(&self.curve.d, &c.square(&self.t)));
+
+        left == right && c.mul(&self.x, &self.y) == c.mul(&self.z, &self.t)
+    }
+
+    /// converts the point to affine coordinates (x, y) with one inversion of Z.
+    /// fails with the shared divisor if Z is not invertible modulo a composite p
+    pub fn to_affine(&self) -> Result<(T, T), T> {
+        let context = &self.curve.context;
+        let z_inverse = context.inv(&self.z)?;
+
+        Ok((
+            context.decode(&context.mul(&self.x, &z_inverse)),
+            context.decode(&context.mul(&self.y, &z_inverse)),
+        ))
+    }
+
+    /// returns the decoded coordinates (X, Y, Z, T)
+    pub fn coordinates(&self) -> (T, T, T, T) {
+        let context = &self.curve.context;
+        (context.decode(&self.x), context.decode(&self.y), context.decode(&self.z), context.decode(&self.t))
+    }
+
+    /// returns the curve the point lies on
+    pub fn curve(&self) -> &TwistedEdwards<T> {
+        &self.curve
+    }
+
+    /// doubles the point (dbl-2008-hwcd), which needs no T
+    pub fn double(&self) -> EdwardsPoint<T> {
+        let c = &self.curve.context;
+
+        let a = c.square(&self.x);
+        let b = c.square(&self.y);
+        let z_squared = c.square(&self.z);
+        let d = match self.curve.a_is_minus_one {
+            true => c.neg(&a),
+            false => c.mul(&self.curve.a, &a),
+        };
+
+        // E = (X + Y)² - A - B, G = D + B, F = G - 2Z², H = D - B
+        let e = c.sub(&c.sub(&c.square(&c.add(&self.x, &self.y)), &a), &b);
+        let g = c.add(&d, &b);
+        let f = c.sub(&g, &c.add(&z_squared, &z_squared));
+        let h = c.sub(&d, &b);
+
+        EdwardsPoint::from_products(e, f, g, h, self.curve.clone())
+    }
+
+    /// adds a point of the same curve with the unified formulas, which also double. on a
+    /// curve with a square a and a non-square d they are complete
+    pub(crate) fn add_point(&self, other: &EdwardsPoint<T>) -> EdwardsPoint<T> {
+        let c = &self.curve.context;
+
+        let (e, f, g, h) = match self.curve.a_is_minus_one {
+            true => {
+                // add-2008-hwcd-3, two multiplications fewer than the general formula
+                let a = c.mul(&c.sub(&self.y, &self.x), &c.sub(&other.y, &other.x));
+                let b = c.mul(&c.add(&self.y, &self.x), &c.add(&other.y, &other.x));
+                let t_product = c.mul(&c.mul(&self.t, &self.curve.d2), &other.t);
+                let d = c.mul(&self.z, &other.z);
+                let d = c.add(&d, &d);
+
+                (c.sub(&b, &a), c.sub(&d, &t_product), c.add(&d, &t_product), c.add(&b, &a))
+            }
+            false => {
+                // add-2008-hwcd
+                let a = c.mul(&self.x, &other.x);
+                let b = c.mul(&self.y, &other.y);
+                let t_product = c.mul(&c.mul(&self.t, &self.curve.d), &other.t);
+                let d = c.mul(&self.z, &other.z);
+                let e = c.sub(&c.sub(&c.mul(&c.add(&self.x, &self.y), &c.add(&other.x, &other.y)), &a), &b);
+
+                (e, c.sub(&d, &t_product), c.add(&d, &t_product), c.sub(&b, &c.mul(&self.curve.a, &a)))
+            }
+        };
+
+        EdwardsPoint::from_products(e, f, g, h, self.curve.clone())
+    }
+
+    /// builds the result X = EF, Y = GH, Z = FG, T = EH shared by all formulas
+    fn from_products(e: T, f: T, g: T, h: T, curve: TwistedEdwards<T>) -> EdwardsPoint<T> {
+        let c = &curve.context;
+
+        EdwardsPoint {
+            x: c.mul(&e, &f),
+            y: c.mul(&g, &h),
+            z: c.mul(&f, &g),
+            t: c.mul(&e, &h),
+            curve,
+        }
+    }
+}
+
+
+impl<T: Number> PartialEq for EdwardsPoint<T> {
+    fn eq(&self, other: &EdwardsPoint<T>) -> bool {
+        // compare (X1/Z1, Y1/Z1) and (X2/Z2, Y2/Z2) without inverting
+        let c = &self.curve.context;
+        self.curve == other.curve &&
+            c.mul(&self.x, &other.z) == c.mul(&other.x, &self.z) &&
+            c.mul(&self.y, &other.z) == c.mul(&other.y, &self.z)
+    }
+}
+
+impl<T: Number> ops::Neg for EdwardsPoint<T> {
+    type Output = EdwardsPoint<T>;
+
+    fn neg(self) -> Self::Output {
+        let context = &self.curve.context;
+        EdwardsPoint { x: context.neg(&self.x), t: context.neg(&self.t), ..self }
+    }
+}
+
+impl_projective_ops!(EdwardsPoint);
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arithmetic::mod_pow;
+    use crate::points::tests::count_points;
+    use rand::Rng;
+
+    /// returns the affine points of a complete curve, where a is a square and d is not
+    fn complete_group(a: i64, d: i64, p: i64) -> Option<Vec<EdwardsPoint<i64>>> {
+        let is_square = |value: i64| mod_pow(&value, &((p - 1) / 2), &p) == 1;
+        if !is_square(a.rem_euclid(p)) || is_square(d.rem_euclid(p)) {
+            return None;
+        }
+
+        let curve = TwistedEdwards::new(a, d, p).ok()?;
+        let points = (0..p).flat_map(|x| (0..p).map(move |y| (x, y)))
+            .map(|(x, y)| EdwardsPoint::new(x, y, curve.clone()))
+            .filter(|point| point.is_on_curve())
+            .collect();
+
+        Some(points)
+    }
+
+    #[test]
+    fn unified_addition_is_a_group_law() {
+        let mut rng = rand::thread_rng();
+
+        // a = -1 runs the fast formulas, -1 is a square for p ≡ 1 mod 4
+        for (a, p) in [(-1, 13), (-1, 17), (-1, 101), (1, 11), (3, 13), (5, 101)] {
+            for points in (2..p).filter_map(|d| complete_group(a, d, p)).take(5) {
+                let identity = EdwardsPoint::new_infinite(points[0].curve.clone());
+                let order = points.len() as i64;
+
+                for _ in 0..20 {
+                    let pick = |rng: &mut rand::rngs::ThreadRng| points[rng.gen_range(0..points.len())].clone();
+                    let (p, q, r) = (pick(&mut rng), pick(&mut rng), pick(&mut rng));
+
+                    let sum = (p.clone() + q.clone()).unwrap();
+                    assert!(sum.is_on_curve());
+                    assert!(sum == (q.clone() + p.clone()).unwrap());
+                    assert!(p.double() == (p.clone() + p.clone()).unwrap());
+                    assert!(((p.clone() + q.clone()).unwrap() + r.clone()).unwrap() == (p.clone() + (q + r).unwrap()).unwrap());
+                    assert!((p.clone() + identity.clone()).unwrap() == p);
+                    assert!((p.clone() - p.clone()).unwrap().is_infinite());
+                    assert!((p * order).is_infinite());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn z12_family_has_torsion_twelve() {
+        for p in [1009i64, 2003, 4001] {
+            let context = Arc::new(ModContext::new(&p));
+
+            for k in 2..8 {
+                let point = match EdwardsPoint::with_z12_torsion(&k, context.clone()) {
+                    Ok(point) => point,
+                    Err(_) => continue,
+                };
+                assert!(point.is_on_curve());
+
+                // count the birationally equivalent montgomery curve B·y² = x³ + A·x² + x
+                // with A = 2(a + d)/(a - d), B = 4/(a - d)
+                let (a, d) = (point.curve.a(), point.curve.d());
+                let inverse = mod_pow(&(a - d).rem_euclid(p), &(p - 2), &p);
+                let (montgomery_a, montgomery_b) = (2 * (a + d) % p * inverse % p, 4 * inverse % p);
+                let order = count_points([montgomery_b, montgomery_a, 1, 0], p);
+
+                assert_eq!(order % 12, 0);
+                assert!((point * order).is_infinite());
+            }
+        }
+    }
+
+    #[test]
+    fn z2z4_family_has_a_minus_one_and_torsion_eight() {
+        for p in [1009i64, 2003, 4001] {
+            let context = Arc::new(ModContext::new(&p));
+
+            for m in 2..20 {
+                let point = match EdwardsPoint::with_z2z4_torsion(&m, context.clone()) {
+                    Ok(point) => point,
+                    Err(_) => continue,
+                };
+                assert!(point.is_on_curve());
+                assert!(point.curve.a_is_minus_one);
+
+                let d = point.curve.d();
+                let inverse = mod_pow(&(-1 - d).rem_euclid(p), &(p - 2), &p);
+                let (montgomery_a, montgomery_b) = (2 * (d - 1) % p * inverse % p, 4 * inverse % p);
+                let order = count_points([montgomery_b, montgomery_a, 1, 0], p);
+
+                assert_eq!(order % 8, 0);
+                assert!((point * order).is_infinite());
+            }
+        }
+    }
+}
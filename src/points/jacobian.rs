@@ -0,0 +1,222 @@
+use std::ops;
+
+use crate::arithmetic::{Number, Reducer};
+use crate::curves::WeierStrass;
+use crate::error::Result;
+use crate::points::WeierStrassPoint;
+
+/// point on a weierstrass curve in jacobian coordinates (X : Y : Z), representing the
+/// affine point (X/Z², Y/Z³). coordinates are kept in the form of the curve's context
+#[derive(Clone)]
+pub struct JacobianPoint<T: Number> {
+    pub(crate) x: T,
+    pub(crate) y: T,
+    pub(crate) z: T,
+    pub(crate) curve: WeierStrass<T>,
+}
+
+impl<T: Number> JacobianPoint<T> {
+    /// creates a point from its jacobian coordinates
+    pub fn new(x: T, y: T, z: T, curve: WeierStrass<T>) -> Self {
+        JacobianPoint {
+            x: curve.context.encode(&x),
+            y: curve.context.encode(&y),
+            z: curve.context.encode(&z),
+            curve,
+        }
+    }
+
+    /// creates the point in infinity (1 : 1 : 0)
+    pub fn new_infinite(curve: WeierStrass<T>) -> Self {
+        JacobianPoint {
+            x: curve.context.one(),
+            y: curve.context.one(),
+            z: T::zero(),
+            curve,
+        }
+    }
+
+    /// converts an affine point, which needs no inversion
+    pub fn from_affine(point: &WeierStrassPoint<T>) -> Self {
+        match point.is_infinite() {
+            true => JacobianPoint::new_infinite(point.curve.clone()),
+            false => JacobianPoint {
+                x: point.x.clone(),
+                y: point.y.clone(),
+                z: point.curve.context.one(),
+                curve: point.curve.clone(),
+            },
+        }
+    }
+
+    /// converts the point to affine coordinates with one inversion of Z.
+    /// fails with the shared divisor if Z is not invertible modulo a composite p
+    pub fn to_affine(&self) -> Result<WeierStrassPoint<T>, T> {
+        if self.is_infinite() {
+            return Ok(WeierStrassPoint::new_infinite(self.curve.clone()));
+        }
+
+        let context = &self.curve.context;
+        let z_inverse = context.inv(&self.z)?;
+        let z_inverse_squared = context.square(&z_inverse);
+        Ok(WeierStrassPoint {
+            x: context.mul(&self.x, &z_inverse_squared),
+            y: context.mul(&self.y, &context.mul(&z_inverse_squared, &z_inverse)),
+            y_infinite: false,
+            curve: self.curve.clone(),
+        })
+    }
+
+    /// returns whether this is the point in infinity
+    pub fn is_infinite(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    /// returns the decoded coordinates (X, Y, Z)
+    pub fn coordinates(&self) -> (T, T, T) {
+        let context = &self.curve.context;
+        (context.decode(&self.x), context.decode(&self.y), context.decode(&self.z))
+    }
+
+    /// returns the curve the point lies on
+    pub fn curve(&self) -> &WeierStrass<T> {
+        &self.curve
+    }
+
+    /// doubles the point (dbl-2007-bl), a point of order two or the point in infinity
+    /// yield Z = 0, so no special case is needed
+    pub fn double(&self) -> JacobianPoint<T> {
+        let c = &self.curve.context;
+
+        let xx = c.square(&self.x);
+        let yy = c.square(&self.y);
+        let yyyy = c.square(&yy);
+        let zz = c.square(&self.z);
+
+        // S = 2((X + YY)² - XX - YYYY), M = 3XX + aZZ²
+        let s = c.sub(&c.sub(&c.square(&c.add(&self.x, &yy)), &xx), &yyyy);
+        let s = c.add(&s, &s);
+        let m = c.add(&c.add(&c.add(&xx, &xx), &xx), &c.mul(&self.curve.a, &c.square(&zz)));
+
+        let x3 = c.sub(&c.square(&m), &c.add(&s, &s));
+        let yyyy8 = c.add(&yyyy, &yyyy);
+        let yyyy8 = c.add(&yyyy8, &yyyy8);
+        let yyyy8 = c.add(&yyyy8, &yyyy8);
+        let y3 = c.sub(&c.mul(&m, &c.sub(&s, &x3)), &yyyy8);
+        let z3 = c.sub(&c.sub(&c.square(&c.add(&self.y, &self.z)), &yy), &zz);
+
+        JacobianPoint { x: x3, y: y3, z: z3, curve: self.curve.clone() }
+    }
+
+    /// adds a point of the same curve (add-2007-bl). the exceptions of the formula are
+    /// handled explicitly: the identity, doubling and adding the inverse point
+    pub(crate) fn add_point(&self, other: &JacobianPoint<T>) -> JacobianPoint<T> {
+        if self.is_infinite() {
+            return other.clone();
+        }
+        if other.is_infinite() {
+            return self.clone();
+        }
+
+        let c = &self.curve.context;
+        let z1z1 = c.square(&self.z);
+        let z2z2 = c.square(&other.z);
+        let u1 = c.mul(&self.x, &z2z2);
+        let u2 = c.mul(&other.x, &z1z1);
+        let s1 = c.mul(&self.y, &c.mul(&other.z, &z2z2));
+        let s2 = c.mul(&other.y, &c.mul(&self.z, &z1z1));
+
+        // H = U2 - U1, r = 2(S2 - S1)
+        let h = c.sub(&u2, &u1);
+        let r = c.sub(&s2, &s1);
+        let r = c.add(&r, &r);
+        if h.is_zero() {
+            return match r.is_zero() {
+                true => self.double(),
+                false => JacobianPoint::new_infinite(self.curve.clone()),
+            };
+        }
+
+        let i = c.square(&c.add(&h, &h));
+        let j = c.mul(&h, &i);
+        let v = c.mul(&u1, &i);
+
+        let x3 = c.sub(&c.sub(&c.square(&r), &j), &c.add(&v, &v));
+        let s1j = c.mul(&s1, &j);
+        let y3 = c.sub(&c.mul(&r, &c.sub(&v, &x3)), &c.add(&s1j, &s1j));
+        let z3 = c.mul(&c.sub(&c.sub(&c.square(&c.add(&self.z, &other.z)), &z1z1), &z2z2), &h);
+
+        JacobianPoint { x: x3, y: y3, z: z3, curve: self.curve.clone() }
+    }
+}
+
+
+impl<T: Number> PartialEq for JacobianPoint<T> {
+    fn eq(&self, other: &JacobianPoint<T>) -> bool {
+        // compare (X1/Z1², Y1/Z1³) and (X2/Z2², Y2/Z2³) without inverting
+        let c = &self.curve.context;
+        match (self.is_infinite(), other.is_infinite()) {
+            (true, true) => self.curve == other.curve,
+            (false, false) => {
+                let (z1z1, z2z2) = (c.square(&self.z), c.square(&other.z));
+                self.curve == other.curve &&
+                    c.mul(&self.x, &z2z2) == c.mul(&other.x, &z1z1) &&
+                    c.mul(&self.y, &c.mul(&z2z2, &other.z)) == c.mul(&other.y, &c.mul(&z1z1, &self.z))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<T: Number> ops::Neg for JacobianPoint<T> {
+    type Output = JacobianPoint<T>;
+
+    fn neg(self) -> Self::Output {
+        JacobianPoint { y: self.curve.context.neg(&self.y), ..self }
+    }
+}
+
+impl_projective_ops!(JacobianPoint);
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::points::tests::{groups, pick, PRIMES};
+    use rand::Rng;
+
+    #[test]
+    fn jacobian_arithmetic_matches_affine() {
+        let mut rng = rand::thread_rng();
+
+        for points in PRIMES.into_iter().flat_map(groups) {
+            for _ in 0..50 {
+                let (p, q) = (pick(&mut rng, &points), pick(&mut rng, &points));
+                let (jacobian_p, jacobian_q) = (JacobianPoint::from_affine(&p), JacobianPoint::from_affine(&q));
+
+                let sum = (jacobian_p.clone() + jacobian_q.clone()).unwrap();
+                assert!(sum.to_affine().unwrap() == (p.clone() + q.clone()).unwrap());
+                assert!(sum == JacobianPoint::from_affine(&(p.clone() + q.clone()).unwrap()));
+                assert!((jacobian_p.clone() - jacobian_q).unwrap().to_affine().unwrap() == (p.clone() - q).unwrap());
+                assert!(jacobian_p.double().to_affine().unwrap() == p.double().unwrap());
+
+                let k = rng.gen_range(-20..20);
+                assert!((k * jacobian_p).to_affine().unwrap() == (p * k).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn conversion_scales_coordinates() {
+        let curve = WeierStrass::new(2, 3, 97i64).unwrap();
+        let (x, y) = (3, 6);
+        assert!(WeierStrassPoint::new(x, y, curve.clone()).is_on_curve());
+
+        // (X : Y : Z) and (λ²X : λ³Y : λZ) are the same point
+        let point = JacobianPoint::new(25 * x, 125 * y, 5, curve.clone());
+        assert!(point == JacobianPoint::from_affine(&WeierStrassPoint::new(x, y, curve.clone())));
+        assert_eq!(point.to_affine().unwrap().x(), Some(x));
+        assert_eq!(point.to_affine().unwrap().y(), Some(y));
+        assert!(JacobianPoint::new_infinite(curve).to_affine().unwrap().is_infinite());
+    }
+}
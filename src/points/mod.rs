@@ -0,0 +1,185 @@
+//! points on the curves and their group operations
+
+/// implements k * P for the number types, rust does not allow it generically
+macro_rules! impl_scalar_mul {
+    ($point:ident) => {
+        impl_scalar_mul!($point; i64, i128, num_bigint::BigInt);
+    };
+    ($point:ident; $($int:ty),*) => {$(
+        impl std::ops::Mul<$point<$int>> for $int {
+            type Output = <$point<$int> as std::ops::Mul<$int>>::Output;
+
+            fn mul(self, point: $point<$int>) -> Self::Output {
+                point * self
+            }
+        }
+    )*};
+}
+
+/// implements addition, subtraction, scalar multiplication and the assign variants for a
+/// point type in projective form. the type provides new_infinite, double, add_point and Neg,
+/// only points of different curves fail, which the assign variants turn into a panic
+macro_rules! impl_projective_ops {
+    ($point:ident) => {
+        impl<T: Number> $point<T> {
+            /// runs double_and_add to multiply the point by a scalar, a negative scalar multiplies -P.
+            /// the scalar may be of another number type, e.g. a big stage 1 bound on an i64 curve
+            pub fn multiply<S: Number>(&self, scalar: &S) -> $point<T> {
+                let mut result = $point::new_infinite(self.curve.clone());
+                if scalar.is_zero() {
+                    return result;
+                }
+
+                let base = match scalar.is_negative() {
+                    true => -self.clone(),
+                    false => self.clone(),
+                };
+
+                for index in (0..=scalar.msb_position()).rev() {
+                    // double
+                    result = result.double();
+
+                    // add
+                    if scalar.bit(index) {
+                        result = result.add_point(&base);
+                    }
+                }
+
+                return result;
+            }
+        }
+
+        impl<T: Number> std::ops::Add<$point<T>> for $point<T> {
+            type Output = crate::error::Result<$point<T>, T>;
+
+            fn add(self, other: $point<T>) -> Self::Output {
+                match self.curve == other.curve {
+                    true => Ok(self.add_point(&other)),
+                    false => Err(crate::error::Error::CurveMismatch),
+                }
+            }
+        }
+
+        impl<T: Number> std::ops::Sub<$point<T>> for $point<T> {
+            type Output = crate::error::Result<$point<T>, T>;
+
+            fn sub(self, other: $point<T>) -> Self::Output {
+                self + (-other)
+            }
+        }
+
+        impl<T: Number> std::ops::Mul<T> for $point<T> {
+            type Output = $point<T>;
+
+            fn mul(self, scalar: T) -> Self::Output {
+                self.multiply(&scalar)
+            }
+        }
+
+        impl_scalar_mul!($point);
+
+        impl<T: Number> std::ops::AddAssign<$point<T>> for $point<T> {
+            fn add_assign(&mut self, other: $point<T>) {
+                *self = (self.clone() + other).unwrap_or_else(|error| panic!("point addition failed: {}", error));
+            }
+        }
+
+        impl<T: Number> std::ops::SubAssign<$point<T>> for $point<T> {
+            fn sub_assign(&mut self, other: $point<T>) {
+                *self = (self.clone() - other).unwrap_or_else(|error| panic!("point subtraction failed: {}", error));
+            }
+        }
+
+        impl<T: Number> std::ops::MulAssign<T> for $point<T> {
+            fn mul_assign(&mut self, scalar: T) {
+                *self = self.multiply(&scalar);
+            }
+        }
+    };
+}
+
+use crate::arithmetic::{ModContext, Number, Reducer};
+use crate::error::{Error, Result};
+
+mod chudnovsky;
+mod edwards;
+mod jacobian;
+mod montgomery;
+mod projective;
+mod weierstrass;
+
+pub use chudnovsky::ChudnovskyPoint;
+pub use edwards::EdwardsPoint;
+pub use jacobian::JacobianPoint;
+pub use montgomery::MontgomeryPoint;
+pub use projective::ProjectivePoint;
+pub use weierstrass::{PseudoPoint, WeierStrassPoint};
+
+/// inverts an encoded number for the curve parametrizations, where a zero marks a
+/// degenerate parameter instead of a divisor of the modulus
+pub(crate) fn invert_parameter<T: Number>(context: &ModContext<T>, number: &T) -> Result<T, T> {
+    match number.is_zero() {
+        true => Err(Error::SingularCurve),
+        false => context.inv(number),
+    }
+}
+
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use rand::Rng;
+
+    use crate::arithmetic::mod_pow;
+    use crate::curves::WeierStrass;
+    use crate::points::WeierStrassPoint;
+
+    pub(crate) const PRIMES: [i64; 5] = [5, 7, 11, 13, 101];
+    const CURVES_PER_PRIME: usize = 20;
+
+    /// returns all points of the first non-singular curves over the field, grouped by curve
+    pub(crate) fn groups(p: i64) -> Vec<Vec<WeierStrassPoint<i64>>> {
+        let mut groups = vec![];
+
+        for (a, b) in (0..p).flat_map(|a| (0..p).map(move |b| (a, b))) {
+            let curve = match WeierStrass::new(a, b, p) {
+                Ok(curve) => curve,
+                Err(_) => continue,
+            };
+
+            let mut points = vec![WeierStrassPoint::new_infinite(curve.clone())];
+            for (x, y) in (0..p).flat_map(|x| (0..p).map(move |y| (x, y))) {
+                let point = WeierStrassPoint::new(x, y, curve.clone());
+                if point.is_on_curve() {
+                    points.push(point);
+                }
+            }
+
+            groups.push(points);
+            if groups.len() == CURVES_PER_PRIME {
+                break;
+            }
+        }
+
+        return groups;
+    }
+
+    /// counts the points of B·y² = x³ + A·x² + a·x + b over a prime field, including the point in infinity
+    pub(crate) fn count_points(coefficients: [i64; 4], p: i64) -> i64 {
+        let [big_b, big_a, a, b] = coefficients.map(|coefficient| coefficient.rem_euclid(p));
+        let b_inverse = mod_pow(&big_b, &(p - 2), &p);
+
+        1 + (0..p).map(|x| {
+            let right = ((x * x % p * x + big_a * x % p * x + a * x + b) % p) * b_inverse % p;
+            match (right, mod_pow(&right, &((p - 1) / 2), &p)) {
+                (0, _) => 1,
+                (_, 1) => 2,
+                _ => 0,
+            }
+        }).sum::<i64>()
+    }
+
+    /// picks a random element of a group
+    pub(crate) fn pick<R: Rng>(rng: &mut R, points: &[WeierStrassPoint<i64>]) -> WeierStrassPoint<i64> {
+        points[rng.gen_range(0..points.len())].clone()
+    }
+}
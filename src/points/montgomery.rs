@@ -0,0 +1,243 @@
+use std::sync::Arc;
+
+use crate::arithmetic::{ModContext, Number, Reducer};
+use crate::curves::MontgomeryCurve;
+use crate::error::Result;
+use crate::points::invert_parameter;
+
+/// x-only point (X : Z) on a montgomery curve, representing the affine x = X/Z of the
+/// points ±P. coordinates are kept in the form of the curve's context
+#[derive(Clone)]
+pub struct MontgomeryPoint<T: Number> {
+    pub(crate) x: T,
+    pub(crate) z: T,
+    pub(crate) curve: MontgomeryCurve<T>,
+}
+
+impl<T: Number> MontgomeryPoint<T> {
+    /// creates a point from its affine x coordinate
+    pub fn new(x: T, curve: MontgomeryCurve<T>) -> Self {
+        MontgomeryPoint {
+            x: curve.context.encode(&x),
+            z: curve.context.one(),
+            curve,
+        }
+    }
+
+    /// creates the point in infinity (1 : 0)
+    pub fn new_infinite(curve: MontgomeryCurve<T>) -> Self {
+        MontgomeryPoint {
+            x: curve.context.one(),
+            z: T::zero(),
+            curve,
+        }
+    }
+
+    /// creates the point suyama's parametrization assigns to sigma. with u = σ² - 5 and
+    /// v = 4σ it is x = u³/v³ on the curve A = (v - u)³(3u + v)/4u³v - 2, B = x³ + Ax² + x,
+    /// so y = 1, and the group order of the curve is divisible by 12. fails with the shared
+    /// divisor if an inversion modulo a composite p fails, and with SingularCurve for
+    /// sigma in 0, ±1, ±3, ±5 and the other degenerate values
+    pub fn with_suyama_sigma(sigma: &T, context: Arc<ModContext<T>>) -> Result<MontgomeryPoint<T>, T> {
+        let c = &context;
+        let number = |value: i64| c.encode(&T::from(value));
+
+        let sigma = c.encode(sigma);
+        let u = c.sub(&c.square(&sigma), &number(5));
+        let v = c.mul(&number(4), &sigma);
+        let (u_cubed, v_cubed) = (c.mul(&c.square(&u), &u), c.mul(&c.square(&v), &v));
+
+        // x = u³/v³ and A = (v - u)³(3u + v)/4u³v - 2
+        let x = c.mul(&u_cubed, &invert_parameter(c, &v_cubed)?);
+        let difference = c.sub(&v, &u);
+        let numerator = c.mul(&c.mul(&c.square(&difference), &difference), &c.add(&c.mul(&number(3), &u), &v));
+        let a = c.sub(&c.mul(&numerator, &invert_parameter(c, &c.mul(&c.mul(&number(4), &u_cubed), &v))?), &number(2));
+
+        // B = x³ + Ax² + x puts the point (x, 1) on the curve
+        let b = c.mul(&c.add(&c.mul(&c.add(&x, &a), &x), &c.one()), &x);
+
+        let curve = MontgomeryCurve::with_context(c.decode(&a), c.decode(&b), context.clone())?;
+        Ok(MontgomeryPoint::new(c.decode(&x), curve))
+    }
+
+    /// returns whether this is the point in infinity
+    pub fn is_infinite(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    /// returns the affine x coordinate with one inversion of Z, None for the point in infinity.
+    /// fails with the shared divisor if Z is not invertible modulo a composite p
+    pub fn x(&self) -> Result<Option<T>, T> {
+        if self.is_infinite() {
+            return Ok(None);
+        }
+
+        let context = &self.curve.context;
+        Ok(Some(context.decode(&context.mul(&self.x, &context.inv(&self.z)?))))
+    }
+
+    /// returns the decoded coordinates (X, Z)
+    pub fn coordinates(&self) -> (T, T) {
+        let context = &self.curve.context;
+        (context.decode(&self.x), context.decode(&self.z))
+    }
+
+    /// returns the curve the point lies on
+    pub fn curve(&self) -> &MontgomeryCurve<T> {
+        &self.curve
+    }
+
+    /// doubles the point (xDBL), a point of order two or the point in infinity yield Z = 0
+    pub fn double(&self) -> MontgomeryPoint<T> {
+        let c = &self.curve.context;
+
+        // (X + Z)², (X - Z)² and their difference 4XZ
+        let sum = c.square(&c.add(&self.x, &self.z));
+        let difference = c.square(&c.sub(&self.x, &self.z));
+        let product = c.sub(&sum, &difference);
+
+        MontgomeryPoint {
+            x: c.mul(&sum, &difference),
+            z: c.mul(&product, &c.add(&difference, &c.mul(&self.curve.a24, &product))),
+            curve: self.curve.clone(),
+        }
+    }
+
+    /// adds a point whose difference to this one is known (xADD). the difference must neither
+    /// be the point in infinity, so both points differ, nor (0, 0), whose X = 0 cancels the sum
+    pub fn differential_add(&self, other: &MontgomeryPoint<T>, difference: &MontgomeryPoint<T>) -> MontgomeryPoint<T> {
+        let c = &self.curve.context;
+
+        // (X1 - Z1)(X2 + Z2) and (X1 + Z1)(X2 - Z2)
+        let u = c.mul(&c.sub(&self.x, &self.z), &c.add(&other.x, &other.z));
+        let v = c.mul(&c.add(&self.x, &self.z), &c.sub(&other.x, &other.z));
+
+        MontgomeryPoint {
+            x: c.mul(&difference.z, &c.square(&c.add(&u, &v))),
+            z: c.mul(&difference.x, &c.square(&c.sub(&u, &v))),
+            curve: self.curve.clone(),
+        }
+    }
+
+    /// runs the montgomery ladder to multiply the point by a scalar. -P has the same x
+    /// coordinate as P, so the sign of the scalar is ignored. the point must not be (0, 0)
+    pub fn ladder<S: Number>(&self, scalar: &S) -> MontgomeryPoint<T> {
+        if scalar.is_zero() || self.is_infinite() {
+            return MontgomeryPoint::new_infinite(self.curve.clone());
+        }
+
+        // keep R1 - R0 = P, so every addition knows its difference
+        let mut low = self.clone();
+        let mut high = self.double();
+
+        for index in (0..scalar.msb_position()).rev() {
+            match scalar.bit(index) {
+                true => {
+                    low = high.differential_add(&low, self);
+                    high = high.double();
+                }
+                false => {
+                    high = low.differential_add(&high, self);
+                    low = low.double();
+                }
+            }
+        }
+
+        return low;
+    }
+}
+
+
+impl<T: Number> PartialEq for MontgomeryPoint<T> {
+    fn eq(&self, other: &MontgomeryPoint<T>) -> bool {
+        // compare X1/Z1 and X2/Z2 without inverting
+        let c = &self.curve.context;
+        match (self.is_infinite(), other.is_infinite()) {
+            (true, true) => self.curve == other.curve,
+            (false, false) => self.curve == other.curve && c.mul(&self.x, &other.z) == c.mul(&other.x, &self.z),
+            _ => false,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arithmetic::mod_inv;
+    use crate::curves::WeierStrass;
+    use crate::points::tests::count_points;
+    use crate::points::WeierStrassPoint;
+    use rand::Rng;
+
+    /// maps B·y² = x³ + A·x² + x to the short weierstrass curve through u = x/B + A/3B, v = y/B
+    fn to_weierstrass(a: i64, b: i64, p: i64) -> Option<WeierStrass<i64>> {
+        let (b_inverse, three_inverse) = (mod_inv(&b, &p).ok()?, mod_inv(&3, &p).ok()?);
+        let weierstrass_a = (3 - a * a).rem_euclid(p) * three_inverse % p * b_inverse % p * b_inverse % p;
+        let weierstrass_b = (2 * a * a * a - 9 * a).rem_euclid(p) * three_inverse % p * three_inverse % p
+            * three_inverse % p * b_inverse % p * b_inverse % p * b_inverse % p;
+        WeierStrass::new(weierstrass_a, weierstrass_b, p).ok()
+    }
+
+    #[test]
+    fn ladder_matches_weierstrass_multiplication() {
+        let mut rng = rand::thread_rng();
+
+        for p in [5i64, 7, 11, 13, 101] {
+            for (a, b) in (0..p).flat_map(|a| (1..p).map(move |b| (a, b))).take(40) {
+                let (curve, weierstrass) = match (MontgomeryCurve::new(a, b, p), to_weierstrass(a, b, p)) {
+                    (Ok(curve), Some(weierstrass)) => (curve, weierstrass),
+                    _ => continue,
+                };
+                let (b_inverse, shift) = (mod_inv(&b, &p).unwrap(), a * mod_inv(&3, &p).unwrap() % p);
+
+                for (x, y) in (0..p).flat_map(|x| (0..p).map(move |y| (x, y))) {
+                    if x == 0 || (b * y * y - x * x * x - a * x * x - x).rem_euclid(p) != 0 {
+                        continue;
+                    }
+
+                    // x = B·u - A/3 maps the weierstrass result back
+                    let point = MontgomeryPoint::new(x, curve.clone());
+                    let weierstrass_point = WeierStrassPoint::new((x + shift) * b_inverse % p, y * b_inverse % p, weierstrass.clone());
+                    let k = rng.gen_range(0..3 * p);
+
+                    let expected = (weierstrass_point * k).unwrap().x().map(|u| (b * u - shift).rem_euclid(p));
+                    assert_eq!(point.ladder(&k).x().unwrap(), expected);
+                    assert!(point.ladder(&k) == point.ladder(&-k));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn suyama_curves_have_order_divisible_by_twelve() {
+        for p in [1009i64, 2003, 4001] {
+            let context = Arc::new(ModContext::new(&p));
+
+            for sigma in 6..20 {
+                let point = MontgomeryPoint::with_suyama_sigma(&sigma, context.clone()).unwrap();
+                let order = count_points([point.curve.b(), point.curve.a(), 1, 0], p);
+
+                assert_eq!(order % 12, 0);
+                assert!(point.ladder(&order).is_infinite());
+            }
+            for sigma in [0, 1, -1, 3, -3, 5, -5] {
+                assert!(MontgomeryPoint::with_suyama_sigma(&sigma, context.clone()).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn differential_addition_matches_ladder() {
+        let curve = MontgomeryCurve::new(6, 1, 1_000_003i64).unwrap();
+        let point = MontgomeryPoint::new(2, curve);
+
+        // (k + 1)P = kP + P with difference (k - 1)P
+        for k in 2..50i64 {
+            let sum = point.ladder(&k).differential_add(&point, &point.ladder(&(k - 1)));
+            assert!(sum == point.ladder(&(k + 1)));
+        }
+        assert!(point.ladder(&2i64) == point.double());
+        assert!(point.ladder(&0i64).is_infinite());
+    }
+}
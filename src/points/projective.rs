@@ -0,0 +1,235 @@
+use std::ops;
+
+use crate::arithmetic::{Number, Reducer};
+use crate::curves::WeierStrass;
+use crate::error::Result;
+use crate::points::WeierStrassPoint;
+
+/// point on a weierstrass curve in homogeneous projective coordinates (X : Y : Z),
+/// representing the affine point (X/Z, Y/Z). coordinates are kept in the form of the curve's context
+#[derive(Clone)]
+pub struct ProjectivePoint<T: Number> {
+    pub(crate) x: T,
+    pub(crate) y: T,
+    pub(crate) z: T,
+    pub(crate) curve: WeierStrass<T>,
+}
+
+impl<T: Number> ProjectivePoint<T> {
+    /// creates a point from its projective coordinates
+    pub fn new(x: T, y: T, z: T, curve: WeierStrass<T>) -> Self {
+        ProjectivePoint {
+            x: curve.context.encode(&x),
+            y: curve.context.encode(&y),
+            z: curve.context.encode(&z),
+            curve,
+        }
+    }
+
+    /// creates the point in infinity (0 : 1 : 0)
+    pub fn new_infinite(curve: WeierStrass<T>) -> Self {
+        ProjectivePoint {
+            x: T::zero(),
+            y: curve.context.one(),
+            z: T::zero(),
+            curve,
+        }
+    }
+
+    /// converts an affine point, which needs no inversion
+    pub fn from_affine(point: &WeierStrassPoint<T>) -> Self {
+        match point.is_infinite() {
+            true => ProjectivePoint::new_infinite(point.curve.clone()),
+            false => ProjectivePoint {
+                x: point.x.clone(),
+                y: point.y.clone(),
+                z: point.curve.context.one(),
+                curve: point.curve.clone(),
+            },
+        }
+    }
+
+    /// converts the point to affine coordinates with one inversion of Z.
+    /// fails with the shared divisor if Z is not invertible modulo a composite p
+    pub fn to_affine(&self) -> Result<WeierStrassPoint<T>, T> {
+        if self.is_infinite() {
+            return Ok(WeierStrassPoint::new_infinite(self.curve.clone()));
+        }
+
+        let context = &self.curve.context;
+        let z_inverse = context.inv(&self.z)?;
+        Ok(WeierStrassPoint {
+            x: context.mul(&self.x, &z_inverse),
+            y: context.mul(&self.y, &z_inverse),
+            y_infinite: false,
+            curve: self.curve.clone(),
+        })
+    }
+
+    /// returns whether this is the point in infinity
+    pub fn is_infinite(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    /// returns the decoded coordinates (X, Y, Z)
+    pub fn coordinates(&self) -> (T, T, T) {
+        let context = &self.curve.context;
+        (context.decode(&self.x), context.decode(&self.y), context.decode(&self.z))
+    }
+
+    /// returns the curve the point lies on
+    pub fn curve(&self) -> &WeierStrass<T> {
+        &self.curve
+    }
+
+    /// doubles the point with the exception-free formula of renes, costello and batina
+    /// (algorithm 3), which also maps the point in infinity to itself
+    pub fn double(&self) -> ProjectivePoint<T> {
+        let c = &self.curve.context;
+        let a = &self.curve.a;
+        let b3 = c.add(&c.add(&self.curve.b, &self.curve.b), &self.curve.b);
+
+        let t0 = c.square(&self.x);
+        let t1 = c.square(&self.y);
+        let t2 = c.square(&self.z);
+        let t3 = c.mul(&self.x, &self.y);
+        let t3 = c.add(&t3, &t3);
+        let z3 = c.mul(&self.x, &self.z);
+        let z3 = c.add(&z3, &z3);
+        let x3 = c.mul(a, &z3);
+        let y3 = c.mul(&b3, &t2);
+        let y3 = c.add(&x3, &y3);
+        let x3 = c.sub(&t1, &y3);
+        let y3 = c.add(&t1, &y3);
+        let y3 = c.mul(&x3, &y3);
+        let x3 = c.mul(&t3, &x3);
+        let z3 = c.mul(&b3, &z3);
+        let t2 = c.mul(a, &t2);
+        let t3 = c.sub(&t0, &t2);
+        let t3 = c.mul(a, &t3);
+        let t3 = c.add(&t3, &z3);
+        let z3 = c.add(&t0, &t0);
+        let t0 = c.add(&z3, &t0);
+        let t0 = c.add(&t0, &t2);
+        let t0 = c.mul(&t0, &t3);
+        let y3 = c.add(&y3, &t0);
+        let t2 = c.mul(&self.y, &self.z);
+        let t2 = c.add(&t2, &t2);
+        let t0 = c.mul(&t2, &t3);
+        let x3 = c.sub(&x3, &t0);
+        let z3 = c.mul(&t2, &t1);
+        let z3 = c.add(&z3, &z3);
+        let z3 = c.add(&z3, &z3);
+
+        ProjectivePoint { x: x3, y: y3, z: z3, curve: self.curve.clone() }
+    }
+
+    /// adds a point of the same curve with the complete formula of renes, costello and batina
+    /// (algorithm 1). it has no special cases, on curves without points of order two it is
+    /// correct for all inputs, including doubling and the point in infinity
+    pub(crate) fn add_point(&self, other: &ProjectivePoint<T>) -> ProjectivePoint<T> {
+        let c = &self.curve.context;
+        let a = &self.curve.a;
+        let b3 = c.add(&c.add(&self.curve.b, &self.curve.b), &self.curve.b);
+
+        let t0 = c.mul(&self.x, &other.x);
+        let t1 = c.mul(&self.y, &other.y);
+        let t2 = c.mul(&self.z, &other.z);
+        let t3 = c.mul(&c.add(&self.x, &self.y), &c.add(&other.x, &other.y));
+        let t3 = c.sub(&t3, &c.add(&t0, &t1));
+        let t4 = c.mul(&c.add(&self.x, &self.z), &c.add(&other.x, &other.z));
+        let t4 = c.sub(&t4, &c.add(&t0, &t2));
+        let t5 = c.mul(&c.add(&self.y, &self.z), &c.add(&other.y, &other.z));
+        let t5 = c.sub(&t5, &c.add(&t1, &t2));
+        let z3 = c.add(&c.mul(a, &t4), &c.mul(&b3, &t2));
+        let x3 = c.sub(&t1, &z3);
+        let z3 = c.add(&t1, &z3);
+        let y3 = c.mul(&x3, &z3);
+        let t1 = c.add(&c.add(&t0, &t0), &t0);
+        let t2 = c.mul(a, &t2);
+        let t4 = c.mul(&b3, &t4);
+        let t1 = c.add(&t1, &t2);
+        let t2 = c.mul(a, &c.sub(&t0, &t2));
+        let t4 = c.add(&t4, &t2);
+        let y3 = c.add(&y3, &c.mul(&t1, &t4));
+        let x3 = c.sub(&c.mul(&t3, &x3), &c.mul(&t5, &t4));
+        let z3 = c.add(&c.mul(&t5, &z3), &c.mul(&t3, &t1));
+
+        ProjectivePoint { x: x3, y: y3, z: z3, curve: self.curve.clone() }
+    }
+}
+
+
+impl<T: Number> PartialEq for ProjectivePoint<T> {
+    fn eq(&self, other: &ProjectivePoint<T>) -> bool {
+        // compare (X1/Z1, Y1/Z1) and (X2/Z2, Y2/Z2) without inverting
+        let c = &self.curve.context;
+        match (self.is_infinite(), other.is_infinite()) {
+            (true, true) => self.curve == other.curve,
+            (false, false) => {
+                self.curve == other.curve &&
+                    c.mul(&self.x, &other.z) == c.mul(&other.x, &self.z) &&
+                    c.mul(&self.y, &other.z) == c.mul(&other.y, &self.z)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl<T: Number> ops::Neg for ProjectivePoint<T> {
+    type Output = ProjectivePoint<T>;
+
+    fn neg(self) -> Self::Output {
+        ProjectivePoint { y: self.curve.context.neg(&self.y), ..self }
+    }
+}
+
+impl_projective_ops!(ProjectivePoint);
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::points::tests::{groups, pick, PRIMES};
+    use rand::Rng;
+
+    /// returns whether x³ + ax + b has no root, so the curve has no point of order two
+    fn has_odd_order(points: &[WeierStrassPoint<i64>]) -> bool {
+        points.iter().all(|point| point.y() != Some(0))
+    }
+
+    #[test]
+    fn projective_arithmetic_matches_affine() {
+        let mut rng = rand::thread_rng();
+
+        for points in PRIMES.into_iter().flat_map(groups).filter(|points| has_odd_order(points)) {
+            for _ in 0..50 {
+                let (p, q) = (pick(&mut rng, &points), pick(&mut rng, &points));
+                let (projective_p, projective_q) = (ProjectivePoint::from_affine(&p), ProjectivePoint::from_affine(&q));
+
+                let sum = (projective_p.clone() + projective_q.clone()).unwrap();
+                assert!(sum.to_affine().unwrap() == (p.clone() + q.clone()).unwrap());
+                assert!(sum == ProjectivePoint::from_affine(&(p.clone() + q.clone()).unwrap()));
+                assert!((projective_p.clone() - projective_q).unwrap().to_affine().unwrap() == (p.clone() - q).unwrap());
+                assert!(projective_p.double().to_affine().unwrap() == p.double().unwrap());
+
+                let k = rng.gen_range(-20..20);
+                assert!((k * projective_p).to_affine().unwrap() == (p * k).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn conversion_scales_coordinates() {
+        let curve = WeierStrass::new(2, 3, 97i64).unwrap();
+        let (x, y) = (3, 6);
+        assert!(WeierStrassPoint::new(x, y, curve.clone()).is_on_curve());
+
+        // (X : Y : Z) and (λX : λY : λZ) are the same point
+        let point = ProjectivePoint::new(5 * x, 5 * y, 5, curve.clone());
+        assert!(point == ProjectivePoint::from_affine(&WeierStrassPoint::new(x, y, curve.clone())));
+        assert_eq!(point.to_affine().unwrap().x(), Some(x));
+        assert_eq!(point.to_affine().unwrap().y(), Some(y));
+        assert!(ProjectivePoint::new_infinite(curve).to_affine().unwrap().is_infinite());
+    }
+}
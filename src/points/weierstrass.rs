@@ -0,0 +1,415 @@
+use std::ops;
+use std::sync::Arc;
+
+use crate::arithmetic::{ModContext, Number, Reducer};
+use crate::curves::WeierStrass;
+use crate::error::{Error, Result};
+use crate::points::{invert_parameter, MontgomeryPoint};
+
+/// point on a weierstrass curve, coordinates are kept in the form of the curve's context
+#[derive(Clone)]
+pub struct WeierStrassPoint<T: Number> {
+    pub(crate) x: T,
+    pub(crate) y: T,
+    pub(crate) y_infinite: bool,
+    pub(crate) curve: WeierStrass<T>,
+}
+
+/// outcome of an operation on a pseudo-curve over Z/nZ with a composite n.
+/// a failed inversion is no error there, its divisor is the result lenstra is looking for
+#[derive(Clone)]
+pub enum PseudoPoint<T: Number> {
+    /// every inversion succeeded and this point is the result
+    Point(WeierStrassPoint<T>),
+    /// a denominator shared the non-trivial divisor d with n, so 1 < d < n
+    FactorFound(T),
+}
+
+impl<T: Number> WeierStrassPoint<T> {
+    /// creates a point from its coordinates
+    pub fn new(x: T, y: T, curve: WeierStrass<T>) -> Self {
+        WeierStrassPoint {
+            x: curve.context.encode(&x),
+            y: curve.context.encode(&y),
+            y_infinite: false,
+            curve,
+        }
+    }
+
+    /// creates the point in infinity, the identity element of the group
+    pub fn new_infinite(curve: WeierStrass<T>) -> Self {
+        WeierStrassPoint {
+            x: T::zero(),
+            y: T::zero(),
+            y_infinite: true,
+            curve,
+        }
+    }
+
+    /// creates the point suyama's parametrization assigns to sigma, see
+    /// MontgomeryPoint::with_suyama_sigma, on the short weierstrass form of its curve.
+    /// the point (x, 1) of B·y² = x³ + A·x² + x maps to (x/B + A/3B, 1/B) on the curve
+    /// with a = (3 - A²)/3B² and b = (2A³ - 9A)/27B³
+    pub fn with_suyama_sigma(sigma: &T, context: Arc<ModContext<T>>) -> Result<WeierStrassPoint<T>, T> {
+        let montgomery = MontgomeryPoint::with_suyama_sigma(sigma, context.clone())?;
+        let c = &context;
+        let (montgomery_a, montgomery_b) = (&montgomery.curve.a, &montgomery.curve.b);
+
+        let b_inverse = invert_parameter(c, montgomery_b)?;
+        let three_inverse = invert_parameter(c, &c.encode(&T::from(3)))?;
+        let (b_inverse_squared, three_inverse_squared) = (c.square(&b_inverse), c.square(&three_inverse));
+
+        let a = c.mul(&c.mul(&c.sub(&c.encode(&T::from(3)), &c.square(montgomery_a)), &three_inverse), &b_inverse_squared);
+        let b = c.mul(
+            &c.mul(&c.sub(&c.mul(&c.encode(&T::from(2)), &c.square(montgomery_a)), &c.encode(&T::from(9))), montgomery_a),
+            &c.mul(&c.mul(&three_inverse_squared, &three_inverse), &c.mul(&b_inverse_squared, &b_inverse)),
+        );
+        let x = c.mul(&c.add(&montgomery.x, &c.mul(montgomery_a, &three_inverse)), &b_inverse);
+
+        let curve = WeierStrass::with_context(c.decode(&a), c.decode(&b), context.clone())?;
+        Ok(WeierStrassPoint::new(c.decode(&x), c.decode(&b_inverse), curve))
+    }
+
+    /// returns whether this is the point in infinity
+    pub fn is_infinite(&self) -> bool {
+        self.y_infinite
+    }
+
+    /// returns the x coordinate, None for the point in infinity
+    pub fn x(&self) -> Option<T> {
+        match self.is_infinite() {
+            true => None,
+            false => Some(self.curve.context.decode(&self.x)),
+        }
+    }
+
+    /// returns the y coordinate, None for the point in infinity
+    pub fn y(&self) -> Option<T> {
+        match self.is_infinite() {
+            true => None,
+            false => Some(self.curve.context.decode(&self.y)),
+        }
+    }
+
+    /// returns the curve the point lies on
+    pub fn curve(&self) -> &WeierStrass<T> {
+        &self.curve
+    }
+
+    /// returns whether the point satisfies y² = x³ + ax + b
+    pub fn is_on_curve(&self) -> bool {
+        if self.is_infinite() {
+            return true;
+        }
+
+        let context = &self.curve.context;
+        let x_cubed_ax = context.mul(&context.add(&context.square(&self.x), &self.curve.a), &self.x);
+        context.square(&self.y) == context.add(&x_cubed_ax, &self.curve.b)
+    }
+
+    /// prints the coordinates to stdout
+    pub fn print(&self) {
+        match (self.x(), self.y()) {
+            (Some(x), Some(y)) => { println!("Point with x={} y={}", x, y) }
+            _ => { println!("Point in infinity") }
+        }
+    }
+
+    /// determines the slope of a point and another one, None for a vertical line.
+    /// fails with the shared divisor if the denominator is not invertible modulo a composite p
+    fn get_slope(&self, other: &WeierStrassPoint<T>) -> Result<Option<T>, T> {
+        // set variables
+        let context = &self.curve.context;
+        let denominator;
+        let numerator;
+
+        // determine slope, all values stay in the form of the context
+        if self == other {
+            // point doubling
+            denominator = context.add(&self.y, &self.y);
+            if denominator.is_zero() { return Ok(None); }
+
+            let x_squared = context.square(&self.x);
+            numerator = context.add(&context.add(&context.add(&x_squared, &x_squared), &x_squared), &self.curve.a);
+        } else {
+            // point addition
+            denominator = context.sub(&other.x, &self.x);
+            if denominator.is_zero() { return Ok(None); }
+
+            numerator = context.sub(&other.y, &self.y);
+        }
+
+        // return integer slope
+        let inverse = context.inv(&denominator)?;
+        Ok(Some(context.mul(&numerator, &inverse)))
+    }
+
+    /// doubles the point
+    pub fn double(&self) -> Result<WeierStrassPoint<T>, T> {
+        self.clone() + self.clone()
+    }
+
+    /// runs double_and_add to multiply the point by a scalar, a negative scalar multiplies -P
+    pub fn multiply(&self, scalar: &T) -> Result<WeierStrassPoint<T>, T> {
+        let mut result = WeierStrassPoint::new_infinite(self.curve.clone());
+        if scalar.is_zero() {
+            return Ok(result);
+        }
+
+        let base = match scalar.is_negative() {
+            true => -self.clone(),
+            false => self.clone(),
+        };
+
+        for index in (0..=scalar.msb_position()).rev() {
+            // double
+            result = result.double()?;
+
+            // add
+            if scalar.bit(index) {
+                result = (result + base.clone())?;
+            }
+        }
+
+        return Ok(result);
+    }
+
+    /// adds two points of a pseudo-curve over Z/nZ, fails only on points of different curves
+    pub fn pseudo_add(&self, other: &WeierStrassPoint<T>) -> Result<PseudoPoint<T>, T> {
+        PseudoPoint::from_result(self.clone() + other.clone())
+    }
+
+    /// doubles a point of a pseudo-curve over Z/nZ
+    pub fn pseudo_double(&self) -> PseudoPoint<T> {
+        PseudoPoint::from_result(self.double()).expect("a point shares the curve with itself")
+    }
+
+    /// multiplies a point of a pseudo-curve over Z/nZ by a scalar, stops at the first failed inversion
+    pub fn pseudo_multiply(&self, scalar: &T) -> PseudoPoint<T> {
+        PseudoPoint::from_result(self.multiply(scalar)).expect("a point shares the curve with itself")
+    }
+}
+
+impl<T: Number> PseudoPoint<T> {
+    /// turns a failed inversion into the divisor it found, other errors are passed on
+    fn from_result(result: Result<WeierStrassPoint<T>, T>) -> Result<PseudoPoint<T>, T> {
+        match result {
+            Ok(point) => Ok(PseudoPoint::Point(point)),
+            Err(Error::NotInvertible { gcd }) => Ok(PseudoPoint::FactorFound(gcd)),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+
+impl<T: Number> PartialEq for WeierStrassPoint<T> {
+    fn eq(&self, other: &WeierStrassPoint<T>) -> bool {
+        match (self.is_infinite(), other.is_infinite()) {
+            (true, true) => self.curve == other.curve,
+            (false, false) => self.x == other.x && self.y == other.y && self.curve == other.curve,
+            _ => false,
+        }
+    }
+}
+
+impl<T: Number> ops::Add<WeierStrassPoint<T>> for WeierStrassPoint<T> {
+    type Output = Result<WeierStrassPoint<T>, T>;
+
+    fn add(self, other: WeierStrassPoint<T>) -> Self::Output {
+        // check for matching curves
+        if self.curve != other.curve {
+            return Err(Error::CurveMismatch);
+        }
+
+        // the point in infinity is the identity
+        if self.is_infinite() {
+            return Ok(other);
+        }
+        if other.is_infinite() {
+            return Ok(self);
+        }
+
+        match self.get_slope(&other)? {
+            Some(slope) => {
+                // determine new coordinates of the new point
+                let context = &self.curve.context;
+                let x = context.sub(&context.sub(&context.square(&slope), &self.x), &other.x);
+                let y = context.sub(&context.mul(&slope, &context.sub(&self.x, &x)), &self.y);
+                Ok(WeierStrassPoint { x, y, y_infinite: false, curve: self.curve })
+            }
+            None => {
+                // vertical line, so the points are inverse to each other
+                Ok(WeierStrassPoint::new_infinite(self.curve))
+            }
+        }
+    }
+}
+
+impl<T: Number> ops::Neg for WeierStrassPoint<T> {
+    type Output = WeierStrassPoint<T>;
+
+    fn neg(self) -> Self::Output {
+        match self.is_infinite() {
+            true => self,
+            false => WeierStrassPoint { y: self.curve.context.neg(&self.y), ..self },
+        }
+    }
+}
+
+impl<T: Number> ops::Sub<WeierStrassPoint<T>> for WeierStrassPoint<T> {
+    type Output = Result<WeierStrassPoint<T>, T>;
+
+    fn sub(self, other: WeierStrassPoint<T>) -> Self::Output {
+        self + (-other)
+    }
+}
+
+impl<T: Number> ops::Mul<T> for WeierStrassPoint<T> {
+    type Output = Result<WeierStrassPoint<T>, T>;
+
+    fn mul(self, scalar: T) -> Self::Output {
+        self.multiply(&scalar)
+    }
+}
+
+impl_scalar_mul!(WeierStrassPoint);
+
+// the assign variants cannot return an error, so they panic where the operators above fail:
+// on points of different curves, or on a denominator that is not invertible modulo a composite
+impl<T: Number> ops::AddAssign<WeierStrassPoint<T>> for WeierStrassPoint<T> {
+    fn add_assign(&mut self, other: WeierStrassPoint<T>) {
+        *self = (self.clone() + other).unwrap_or_else(|error| panic!("point addition failed: {}", error));
+    }
+}
+
+impl<T: Number> ops::SubAssign<WeierStrassPoint<T>> for WeierStrassPoint<T> {
+    fn sub_assign(&mut self, other: WeierStrassPoint<T>) {
+        *self = (self.clone() - other).unwrap_or_else(|error| panic!("point subtraction failed: {}", error));
+    }
+}
+
+impl<T: Number> ops::MulAssign<T> for WeierStrassPoint<T> {
+    fn mul_assign(&mut self, scalar: T) {
+        *self = self.multiply(&scalar).unwrap_or_else(|error| panic!("scalar multiplication failed: {}", error));
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::points::tests::{count_points, groups, pick, PRIMES};
+    use rand::Rng;
+
+    #[test]
+    fn identity_and_inverses() {
+        for points in PRIMES.into_iter().flat_map(groups) {
+            let identity = WeierStrassPoint::new_infinite(points[0].curve.clone());
+
+            for point in &points {
+                assert!((point.clone() + identity.clone()).unwrap() == *point);
+                assert!((identity.clone() + point.clone()).unwrap() == *point);
+                assert!((-point.clone()).is_on_curve());
+                assert!((point.clone() + -point.clone()).unwrap() == identity);
+                assert!((point.clone() - point.clone()).unwrap() == identity);
+            }
+        }
+    }
+
+    #[test]
+    fn addition_is_closed_commutative_and_associative() {
+        let mut rng = rand::thread_rng();
+
+        for points in PRIMES.into_iter().flat_map(groups) {
+            for _ in 0..50 {
+                let (p, q, r) = (pick(&mut rng, &points), pick(&mut rng, &points), pick(&mut rng, &points));
+
+                let sum = (p.clone() + q.clone()).unwrap();
+                assert!(sum.is_on_curve());
+                assert!(sum == (q.clone() + p.clone()).unwrap());
+
+                let left = ((p.clone() + q.clone()).unwrap() + r.clone()).unwrap();
+                let right = (p + (q + r).unwrap()).unwrap();
+                assert!(left == right);
+            }
+        }
+    }
+
+    #[test]
+    fn scalar_multiplication_is_repeated_addition() {
+        let mut rng = rand::thread_rng();
+
+        for points in PRIMES.into_iter().flat_map(groups) {
+            let order = points.len() as i64;
+            let point = pick(&mut rng, &points);
+
+            // k * P = P + ... + P, also for negative k
+            let mut sum = WeierStrassPoint::new_infinite(point.curve.clone());
+            for k in 0..=2 * order {
+                assert!((point.clone() * k).unwrap() == sum);
+                assert!((-k * point.clone()).unwrap() == -sum.clone());
+                sum += point.clone();
+            }
+
+            // the group order annihilates every point
+            assert!((order * point.clone()).unwrap().is_infinite());
+
+            // (k + l) * P = k * P + l * P
+            let (k, l) = (rng.gen_range(-order..order), rng.gen_range(-order..order));
+            let mut assigned = point.clone();
+            assigned *= k;
+            assigned -= (-l * point.clone()).unwrap();
+            assert!((point * (k + l)).unwrap() == assigned);
+        }
+    }
+
+    #[test]
+    fn suyama_curves_keep_their_order_in_weierstrass_form() {
+        for p in [1009i64, 2003, 4001] {
+            let context = Arc::new(ModContext::new(&p));
+
+            for sigma in 6..20 {
+                let point = WeierStrassPoint::with_suyama_sigma(&sigma, context.clone()).unwrap();
+                let order = count_points([1, 0, point.curve.a(), point.curve.b()], p);
+
+                assert!(point.is_on_curve());
+                assert_eq!(order % 12, 0);
+                assert!((point * order).unwrap().is_infinite());
+            }
+        }
+    }
+
+    #[test]
+    fn pseudo_curves_surface_the_divisor() {
+        // 360360 = lcm(1..=15) annihilates every curve group modulo 5 and 7 (orders up to 13)
+        let n: i64 = 5 * 7;
+        let mut found = 0;
+
+        for a in 1..n {
+            let curve = match WeierStrass::new(a, n - a, n) {
+                Ok(curve) => curve,
+                Err(_) => continue,
+            };
+            let point = WeierStrassPoint::new(1, 1, curve);
+
+            match point.pseudo_multiply(&360360) {
+                PseudoPoint::Point(point) => assert!(point.is_on_curve()),
+                PseudoPoint::FactorFound(divisor) => {
+                    assert!(divisor == 5 || divisor == 7);
+                    found += 1;
+                }
+            }
+        }
+
+        assert!(found > 0);
+    }
+
+    #[test]
+    fn operands_on_different_curves_are_rejected() {
+        let first = WeierStrassPoint::new(0, 1, WeierStrass::new(1, 1, 5i64).unwrap());
+        let second = WeierStrassPoint::new(0, 1, WeierStrass::new(2, 1, 5i64).unwrap());
+
+        assert!(matches!(first.clone() + second.clone(), Err(Error::CurveMismatch)));
+        assert!(matches!(first - second, Err(Error::CurveMismatch)));
+    }
+}
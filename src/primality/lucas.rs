@@ -0,0 +1,154 @@
+use crate::arithmetic::{add_mod, mod_mul, sub_mod, Number};
+
+/// returns the jacobi symbol (a/n) for an odd positive n, 0 if both share a divisor
+pub fn jacobi_symbol<T: Number>(a: &T, n: &T) -> i8 {
+    let (mut a, mut n) = (a.mod_floor(n), n.clone());
+    let (three, five, eight) = (T::from(3), T::from(5), T::from(8));
+    let mut symbol = 1;
+
+    while !a.is_zero() {
+        // (2/n) = -1 for n = 3, 5 mod 8
+        while a.is_even() {
+            a = a.div_floor(&T::from(2));
+            let residue = n.mod_floor(&eight);
+            if residue == three || residue == five {
+                symbol = -symbol;
+            }
+        }
+
+        // quadratic reciprocity flips the sign if both are 3 mod 4
+        std::mem::swap(&mut a, &mut n);
+        if a.mod_floor(&T::from(4)) == three && n.mod_floor(&T::from(4)) == three {
+            symbol = -symbol;
+        }
+        a = a.mod_floor(&n);
+    }
+
+    match n.is_one() {
+        true => symbol,
+        false => 0,
+    }
+}
+
+/// runs the strong lucas probable prime test with the parameters of selfridge: the first D of
+/// 5, -7, 9, -11, ... with (D/n) = -1, P = 1 and Q = (1 - D)/4. with n + 1 = d·2^s and d odd, a
+/// prime satisfies U_d = 0 or V_(d·2^r) = 0 for some r < s. the number must be odd
+pub fn strong_lucas<T: Number>(number: &T) -> bool {
+    if *number < T::from(2) || number.is_even() {
+        return *number == T::from(2);
+    }
+
+    // a square has no D with (D/n) = -1, so it is excluded before the search
+    let root = number.sqrt();
+    if root.clone() * root == *number {
+        return false;
+    }
+
+    let mut d = T::from(5);
+    loop {
+        match jacobi_symbol(&d, number) {
+            -1 => break,
+            0 if d.abs() != *number => return false,
+            _ => {}
+        }
+
+        d = match d.is_positive() {
+            true => -(d + T::from(2)),
+            false => -(d - T::from(2)),
+        };
+    }
+
+    // n + 1 = d·2^s with odd d, starting from (n + 1)/2 so n + 1 cannot overflow the type
+    let (mut odd, mut shift) = (number.div_floor(&T::from(2)) + T::one(), 1);
+    while odd.is_even() {
+        odd = odd.div_floor(&T::from(2));
+        shift += 1;
+    }
+
+    let q = (T::one() - d.clone()).div_floor(&T::from(4));
+    let (u, mut v, mut q_power) = lucas_sequence(&odd, &d.mod_floor(number), &q.mod_floor(number), number);
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+
+    // V_2k = V_k² - 2Q^k
+    for _ in 1..shift {
+        v = sub_mod(&mod_mul(&v, &v, number), &add_mod(&q_power, &q_power, number), number);
+        if v.is_zero() {
+            return true;
+        }
+        q_power = mod_mul(&q_power, &q_power, number);
+    }
+
+    return false;
+}
+
+/// returns U_k, V_k and Q^k of the lucas sequences with P = 1 modulo an odd n, running from
+/// the most significant bit with the doubling and increment formulas
+fn lucas_sequence<T: Number>(k: &T, d: &T, q: &T, n: &T) -> (T, T, T) {
+    let (mut u, mut v, mut q_power) = (T::one(), T::one(), q.clone());
+
+    for index in (0..k.msb_position()).rev() {
+        // U_2k = U_k·V_k, V_2k = V_k² - 2Q^k
+        u = mod_mul(&u, &v, n);
+        v = sub_mod(&mod_mul(&v, &v, n), &add_mod(&q_power, &q_power, n), n);
+        q_power = mod_mul(&q_power, &q_power, n);
+
+        // U_2k+1 = (U_2k + V_2k)/2, V_2k+1 = (D·U_2k + V_2k)/2
+        if k.bit(index) {
+            let next_u = half(&add_mod(&u, &v, n), n);
+            v = half(&add_mod(&mod_mul(d, &u, n), &v, n), n);
+            u = next_u;
+            q_power = mod_mul(&q_power, q, n);
+        }
+    }
+
+    return (u, v, q_power);
+}
+
+/// halves a residue modulo an odd n, an odd residue is shifted by n first
+fn half<T: Number>(number: &T, n: &T) -> T {
+    let two = T::from(2);
+
+    // (x + n)/2 without forming x + n, which could overflow the type
+    match number.is_odd() {
+        true => number.div_floor(&two) + n.div_floor(&two) + T::one(),
+        false => number.div_floor(&two),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arithmetic::mod_pow;
+    use crate::primality::tests::trial_division;
+
+    #[test]
+    fn jacobi_symbol_matches_euler_criterion() {
+        for n in [3i64, 5, 7, 11, 13, 101, 1_009] {
+            for a in -50..50i64 {
+                let euler = mod_pow(&a.rem_euclid(n), &((n - 1) / 2), &n);
+                let expected = match euler {
+                    0 => 0,
+                    1 => 1,
+                    _ => -1,
+                };
+                assert_eq!(jacobi_symbol(&a, &n), expected);
+            }
+        }
+        assert_eq!(jacobi_symbol(&2i64, &15), 1);
+        assert_eq!(jacobi_symbol(&7i64, &15), -1);
+        assert_eq!(jacobi_symbol(&5i64, &15), 0);
+    }
+
+    #[test]
+    fn strong_lucas_pseudoprimes_are_known_ones() {
+        // all strong lucas pseudoprimes below 20000 with the parameters of selfridge
+        let pseudoprimes = [5_459i64, 5_777, 10_877, 16_109, 18_971];
+        for number in (3..20_000i64).step_by(2) {
+            let prime = trial_division(number);
+            assert_eq!(strong_lucas(&number), prime || pseudoprimes.contains(&number), "{}", number);
+        }
+    }
+}
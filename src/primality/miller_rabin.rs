@@ -0,0 +1,99 @@
+use crate::arithmetic::{mod_pow, Number};
+
+/// bases that decide the miller-rabin test for every number below the bound next to them,
+/// the last two cover 2^64 and 2^81
+const WITNESS_SETS: [(u128, &[i64]); 6] = [
+    (3_215_031_751, &[2, 3, 5, 7]),
+    (3_474_749_660_383, &[2, 3, 5, 7, 11, 13]),
+    (341_550_071_728_321, &[2, 3, 5, 7, 11, 13, 17]),
+    (3_825_123_056_546_413_051, &[2, 3, 5, 7, 11, 13, 17, 19, 23]),
+    (318_665_857_834_031_151_167_461, &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37]),
+    (3_317_044_064_679_887_385_961_981, &[2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41]),
+];
+
+/// runs the strong probable prime test to a base: with n - 1 = d·2^s and d odd, a prime
+/// satisfies a^d = 1 or a^(d·2^r) = -1 for some r < s. false for even numbers and numbers below
+/// three, for which n - 1 has no such split
+pub fn miller_rabin<T: Number>(number: &T, base: &T) -> bool {
+    if *number < T::from(3) || number.is_even() {
+        return false;
+    }
+
+    // n - 1 = d·2^s with odd d
+    let minus_one = number.clone() - T::one();
+    let (mut odd, mut shift) = (minus_one.clone(), 0);
+    while odd.is_even() {
+        odd = odd.div_floor(&T::from(2));
+        shift += 1;
+    }
+
+    let mut value = mod_pow(base, &odd, number);
+    if value.is_one() || value == minus_one {
+        return true;
+    }
+
+    (1..shift).any(|_| {
+        value = value.mul_mod(&value, number);
+        value == minus_one
+    })
+}
+
+/// decides primality with a deterministic witness set, None for numbers above 3.3·10^24
+/// for which no witness set is known
+pub fn is_prime_deterministic<T: Number>(number: &T) -> Option<bool> {
+    if *number < T::from(2) {
+        return Some(false);
+    }
+    if number.is_even() {
+        return Some(*number == T::from(2));
+    }
+
+    // all bounds fit 82 bits, so only numbers of at most two limbs are compared
+    if number.msb_position() >= 82 {
+        return None;
+    }
+    let value = number.to_limbs().iter().rev().fold(0u128, |value, limb| value << 64 | *limb as u128);
+    let (_, witnesses) = WITNESS_SETS.iter().find(|(bound, _)| value < *bound)?;
+
+    Some(witnesses.iter().map(|witness| T::from(*witness)).all(|witness| {
+        // a prime base only divides the prime itself
+        match number.is_multiple_of(&witness) {
+            true => *number == witness,
+            false => miller_rabin(number, &witness),
+        }
+    }))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primality::tests::trial_division;
+
+    #[test]
+    fn deterministic_test_matches_trial_division() {
+        for number in 0..100_000i64 {
+            assert_eq!(is_prime_deterministic(&number), Some(trial_division(number)));
+        }
+    }
+
+    #[test]
+    fn strong_pseudoprimes_are_rejected() {
+        // strong pseudoprimes to base 2 and the smallest one to all bases up to 37
+        for composite in [2_047i128, 3_277, 4_033, 4_681, 8_321] {
+            assert!(miller_rabin(&composite, &2));
+            assert_eq!(is_prime_deterministic(&composite), Some(false));
+        }
+        for composite in [3_215_031_751i128, 3_825_123_056_546_413_051, 318_665_857_834_031_151_167_461] {
+            assert_eq!(is_prime_deterministic(&composite), Some(false));
+        }
+
+        for number in [-3i64, -1, 0, 1, 2, 4, 1 << 40] {
+            assert!(!miller_rabin(&number, &2), "{}", number);
+        }
+
+        assert_eq!(is_prime_deterministic(&i64::MAX), Some(false));
+        assert_eq!(is_prime_deterministic(&((1i128 << 61) - 1)), Some(true));
+        assert_eq!(is_prime_deterministic(&((1i128 << 89) - 1)), None);
+    }
+}
@@ -0,0 +1,70 @@
+//! primality tests: miller-rabin, the strong lucas test and their combination baillie-psw
+
+mod lucas;
+mod miller_rabin;
+
+pub use lucas::{jacobi_symbol, strong_lucas};
+pub use miller_rabin::{is_prime_deterministic, miller_rabin};
+
+use crate::arithmetic::Number;
+
+/// primes divided out before the probable prime tests
+const SMALL_PRIMES: [i64; 25] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97];
+
+/// returns whether a number is prime. below 3.3·10^24 the miller-rabin test with the primes up
+/// to 41 decides it, above no deterministic witness set is known and baillie-psw runs instead
+pub fn is_prime<T: Number>(number: &T) -> bool {
+    match is_prime_deterministic(number) {
+        Some(prime) => prime,
+        None => bpsw(number),
+    }
+}
+
+/// runs the baillie-psw test: trial division by small primes, a strong probable prime test to
+/// base 2 and a strong lucas test. no composite passing both is known
+pub fn bpsw<T: Number>(number: &T) -> bool {
+    if *number < T::from(2) {
+        return false;
+    }
+
+    for prime in SMALL_PRIMES.map(T::from) {
+        if number.is_multiple_of(&prime) {
+            return *number == prime;
+        }
+    }
+
+    miller_rabin(number, &T::from(2)) && strong_lucas(number)
+}
+
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use num_bigint::BigInt;
+
+    /// decides primality by trial division
+    pub(crate) fn trial_division(number: i64) -> bool {
+        number >= 2 && (2..).take_while(|divisor| divisor * divisor <= number).all(|divisor| number % divisor != 0)
+    }
+
+    #[test]
+    fn bpsw_matches_the_deterministic_test() {
+        for number in (0..20_000i64).chain(1_000_000_000..1_000_002_000) {
+            assert_eq!(bpsw(&number), is_prime_deterministic(&number).unwrap(), "{}", number);
+        }
+    }
+
+    #[test]
+    fn large_primes_and_composites() {
+        // mersenne numbers 2^p - 1 are prime for these p and composite for the others
+        for (exponent, prime) in [(61, true), (67, false), (89, true), (101, false), (107, true), (127, true), (521, true), (523, false)] {
+            let number = (BigInt::from(1) << exponent) - 1;
+            assert_eq!(is_prime(&number), prime, "2^{} - 1", exponent);
+        }
+        assert!(is_prime(&i128::MAX));
+
+        let (p, q) = (BigInt::from(10).pow(30) + 57, BigInt::from(10).pow(31) + 33);
+        assert!(is_prime(&p) && is_prime(&q));
+        assert!(!is_prime(&(p * q)));
+    }
+}
@@ -0,0 +1,151 @@
+//! segmented sieve of eratosthenes and iterators over the primes
+
+/// numbers sieved at once, small enough to stay in the cache
+const SEGMENT: u64 = 1 << 16;
+
+/// iterator over the primes of a range. the range is sieved segment by segment, so the memory
+/// only grows with the segment and the primes up to the square root of the current segment
+pub struct Primes {
+    /// primes up to the square root of the current segment, which cross out the composites
+    base: Vec<u64>,
+    base_limit: u64,
+    /// start of the next segment and the inclusive end of the range
+    low: u64,
+    high: u64,
+    /// primes of the current segment not yet returned, in reverse order
+    pending: Vec<u64>,
+}
+
+impl Primes {
+    /// iterates over all primes
+    pub fn new() -> Self {
+        Primes::between(2, u64::MAX)
+    }
+
+    /// iterates over the primes up to a bound, including it
+    pub fn up_to(bound: u64) -> Self {
+        Primes::between(2, bound)
+    }
+
+    /// iterates over the primes in low..=high
+    pub fn between(low: u64, high: u64) -> Self {
+        Primes { base: vec![], base_limit: 1, low: low.max(2), high, pending: vec![] }
+    }
+
+    /// sieves the next segment, returns false once the range is exhausted
+    fn sieve_segment(&mut self) -> bool {
+        if self.low > self.high {
+            return false;
+        }
+        let end = self.high.min(self.low.saturating_add(SEGMENT - 1));
+
+        // the base primes have to reach the square root of the segment end
+        let root = end.isqrt();
+        if self.base_limit < root {
+            self.base_limit = root.max(2 * self.base_limit).min(u32::MAX as u64);
+            self.base = simple_sieve(self.base_limit);
+        }
+
+        let mut composite = vec![false; (end - self.low + 1) as usize];
+        for &prime in self.base.iter().take_while(|prime| **prime <= root) {
+            let Some(first) = first_multiple(prime, self.low, end) else {
+                continue;
+            };
+            for multiple in (first..=end).step_by(prime as usize) {
+                composite[(multiple - self.low) as usize] = true;
+            }
+        }
+
+        self.pending = (self.low..=end).rev().filter(|number| !composite[(number - self.low) as usize]).collect();
+        self.low = end.saturating_add(1);
+        if end == u64::MAX {
+            self.high = 0;
+        }
+        return true;
+    }
+}
+
+impl Default for Primes {
+    fn default() -> Self {
+        Primes::new()
+    }
+}
+
+impl Iterator for Primes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        while self.pending.is_empty() {
+            if !self.sieve_segment() {
+                return None;
+            }
+        }
+
+        self.pending.pop()
+    }
+}
+
+/// returns the primes up to a bound, including it
+pub fn primes_up_to(bound: u64) -> Vec<u64> {
+    Primes::up_to(bound).collect()
+}
+
+/// returns the first multiple of the prime in low..=end to cross out, None if there is none.
+/// it starts at p², smaller multiples were crossed out by smaller primes, and it does not
+/// overflow in the last segment below u64::MAX
+fn first_multiple(prime: u64, low: u64, end: u64) -> Option<u64> {
+    let first = low.checked_add((prime - low % prime) % prime)?.max(prime * prime);
+    (first <= end).then_some(first)
+}
+
+/// sieves the primes up to a small bound in one piece
+fn simple_sieve(bound: u64) -> Vec<u64> {
+    let mut composite = vec![false; bound as usize + 1];
+    let mut primes = vec![];
+
+    for number in 2..=bound {
+        if composite[number as usize] {
+            continue;
+        }
+
+        primes.push(number);
+        for multiple in (number * number..=bound).step_by(number as usize) {
+            composite[multiple as usize] = true;
+        }
+    }
+
+    return primes;
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segments_match_the_simple_sieve() {
+        let expected = simple_sieve(1_000_000);
+
+        assert_eq!(primes_up_to(1_000_000), expected);
+        assert_eq!(Primes::new().take(expected.len()).collect::<Vec<_>>(), expected);
+        assert_eq!(primes_up_to(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(primes_up_to(1), vec![]);
+
+        let (low, high) = (123_456, 654_321);
+        let between = expected.iter().copied().filter(|prime| (low..=high).contains(prime)).collect::<Vec<_>>();
+        assert_eq!(Primes::between(low, high).collect::<Vec<_>>(), between);
+    }
+
+    #[test]
+    fn ranges_far_out() {
+        // only the primes up to 10^6 are needed to sieve after 10^12
+        let after = Primes::between(1_000_000_000_000, 1_000_000_000_100).collect::<Vec<_>>();
+        assert_eq!(after, vec![1_000_000_000_039, 1_000_000_000_061, 1_000_000_000_063, 1_000_000_000_091]);
+
+        // 2^64 - 1 = 4 mod 11, so 11 has no multiple in the last two numbers
+        assert_eq!(first_multiple(7, 100, 200), Some(105));
+        assert_eq!(first_multiple(7, 10, 100), Some(49));
+        assert_eq!(first_multiple(7, u64::MAX - 1, u64::MAX), Some(u64::MAX - 1));
+        assert_eq!(first_multiple(11, u64::MAX - 1, u64::MAX), None);
+    }
+}